@@ -3,7 +3,89 @@
 
 use std::time::Instant;
 use tauri::Window;
-use tauri_plugin_egui::{egui, AppHandleExt};
+use tauri_plugin_egui::{egui, AppHandleExt, EguiApp, Frame};
+
+struct DemoApp {
+  counter: i32,
+  text: String,
+  start_time: Instant,
+}
+
+impl Default for DemoApp {
+  fn default() -> Self {
+    Self {
+      counter: 0,
+      text: "Edit me".to_string(),
+      start_time: Instant::now(),
+    }
+  }
+}
+
+impl EguiApp for DemoApp {
+  fn update(&mut self, ctx: &egui::Context, frame: &mut Frame) {
+    egui::CentralPanel::default().show(ctx, |ui| {
+      ui.add_space(28.0);
+      ui.heading("Hello from Egui!");
+      ui.label("This is rendered natively with egui!");
+      ui.separator();
+
+      if ui.button("Click me").clicked() {
+        println!("Egui button clicked!");
+      }
+
+      ui.horizontal(|ui| {
+        ui.label("Counter:");
+        if ui.button("+").clicked() {
+          self.counter += 1;
+        }
+        ui.label(format!("{}", self.counter));
+        if ui.button("-").clicked() {
+          self.counter -= 1;
+        }
+      });
+
+      ui.separator();
+
+      // Cursor test - hover over different areas to see cursor changes
+      ui.label("Hover over different areas to test cursor changes:");
+      ui.horizontal(|ui| {
+        // Force hand cursor for this button
+        if ui
+          .add(egui::Button::new("Click me (hand cursor)"))
+          .on_hover_cursor(egui::CursorIcon::PointingHand)
+          .clicked()
+        {
+          println!("Button clicked!");
+        }
+
+        // Force text cursor for this text edit
+        ui.add(egui::TextEdit::singleline(&mut self.text).hint_text("Type here (text cursor)"));
+
+        // Force help cursor
+        ui.add(egui::Button::new("Help (?)")).on_hover_cursor(egui::CursorIcon::Help);
+      });
+
+      ui.separator();
+
+      // Timer demonstration - shows continuous rendering
+      let elapsed = self.start_time.elapsed().as_secs_f32();
+      ui.label(format!("Timer: {:.1}s", elapsed));
+
+      // Request repaint to keep the timer updating
+      ctx.request_repaint();
+
+      ui.separator();
+
+      if ui.button("Close window").clicked() {
+        frame.close();
+      }
+    });
+  }
+
+  fn on_exit(&mut self) {
+    println!("Egui app exited with counter at {}", self.counter);
+  }
+}
 
 fn main() {
   tauri::Builder::default()
@@ -19,70 +101,10 @@ fn main() {
         .title_bar_style(tauri::TitleBarStyle::Overlay)
         .build()?;
 
-      app.handle().start_egui_for_window(
-        "main",
-        Box::new(|ctx| {
-          egui::CentralPanel::default().show(ctx, |ui| {
-            ui.add_space(28.0);
-            ui.heading("Hello from Egui!");
-            ui.label("This is rendered natively with egui!");
-            ui.separator();
-
-            if ui.button("Click me").clicked() {
-              println!("Egui button clicked!");
-            }
-
-            ui.horizontal(|ui| {
-              ui.label("Counter:");
-              // Note: just for demo, in a real app you'd want persistent state
-              static mut COUNTER: i32 = 0;
-              unsafe {
-                if ui.button("+").clicked() {
-                  COUNTER += 1;
-                }
-                ui.label(format!("{}", COUNTER));
-                if ui.button("-").clicked() {
-                  COUNTER -= 1;
-                }
-              }
-            });
-
-            ui.separator();
-
-            // Cursor test - hover over different areas to see cursor changes
-            ui.label("Hover over different areas to test cursor changes:");
-            ui.horizontal(|ui| {
-                // Force hand cursor for this button
-                if ui.add(egui::Button::new("Click me (hand cursor)")).on_hover_cursor(egui::CursorIcon::PointingHand).clicked() {
-                    println!("Button clicked!");
-                }
-                
-                // Force text cursor for this text edit
-                ui.add(egui::TextEdit::singleline(&mut "Edit me".to_string()).hint_text("Type here (text cursor)"));
-                
-                // Force help cursor
-                ui.add(egui::Button::new("Help (?)")).on_hover_cursor(egui::CursorIcon::Help);
-            });
-
-            ui.separator();
-
-            // Timer demonstration - shows continuous rendering
-            static mut START_TIME: Option<Instant> = None;
-            unsafe {
-              if START_TIME.is_none() {
-                START_TIME = Some(Instant::now());
-              }
-              if let Some(start) = START_TIME {
-                let elapsed = start.elapsed().as_secs_f32();
-                ui.label(format!("Timer: {:.1}s", elapsed));
-
-                // Request repaint to keep the timer updating
-                ctx.request_repaint();
-              }
-            }
-          });
-        }),
-      )?;
+      // Third: start rendering an app that owns its state
+      app
+        .handle()
+        .start_egui_app_for_window("main", DemoApp::default())?;
 
       Ok(())
     })
@@ -0,0 +1,130 @@
+use tauri::AppHandle;
+
+/// An egui application that owns its state, drawn inside a Tauri window.
+///
+/// Modeled on `eframe::App`. Plain closures taking `&egui::Context` implement
+/// this trait too, so they can be passed wherever an `EguiApp` is expected.
+pub trait EguiApp {
+    /// Called each time the UI needs repainting.
+    ///
+    /// Put your widgets into a [`egui::CentralPanel`], [`egui::SidePanel`], [`egui::Window`] etc.
+    fn update(&mut self, ctx: &egui::Context, frame: &mut Frame);
+
+    /// Called when the window stops rendering egui, before [`Self::on_exit`],
+    /// if a [`Storage`] was set with [`crate::Builder::storage`].
+    fn save(&mut self, _storage: &mut dyn Storage) {}
+
+    /// Called once when the window stops rendering egui, either because it was
+    /// closed or because of [`crate::AppHandleExt::stop_egui_for_window`].
+    /// Close requests the app prevents keep egui running.
+    fn on_exit(&mut self) {}
+
+    /// The color the window is cleared with before egui paints, in gamma-space RGBA.
+    /// Only the region is, for egui drawn into a [`crate::EguiRegion`].
+    ///
+    /// Defaults to fully transparent so the window background shows through.
+    fn clear_color(&self, _visuals: &egui::Visuals) -> [f32; 4] {
+        [0.0, 0.0, 0.0, 0.0]
+    }
+}
+
+impl<F: FnMut(&egui::Context)> EguiApp for F {
+    fn update(&mut self, ctx: &egui::Context, _frame: &mut Frame) {
+        self(ctx)
+    }
+}
+
+/// A key-value store apps can persist their state into, see [`EguiApp::save`].
+pub trait Storage: Send {
+    fn get_string(&self, key: &str) -> Option<String>;
+
+    fn set_string(&mut self, key: &str, value: String);
+
+    /// Write any pending changes to disk (or wherever the storage lives).
+    fn flush(&mut self) {}
+}
+
+/// A command queued by the UI, run against the window once the frame is done.
+pub type WindowCommand = Box<dyn FnOnce(&tauri::Window)>;
+
+/// Per-frame access to the Tauri window an [`EguiApp`] is being drawn in.
+///
+/// Like the app itself, a frame never leaves the event loop thread:
+///
+/// ```compile_fail,E0277
+/// fn assert_send<T: Send>() {}
+/// assert_send::<tauri_plugin_egui::Frame>();
+/// ```
+pub struct Frame {
+    label: String,
+    window: tauri::Window,
+    app_handle: AppHandle,
+    frame_nr: u64,
+    cpu_usage: Option<f32>,
+    commands: Vec<WindowCommand>,
+}
+
+impl Frame {
+    pub(crate) fn new(window: tauri::Window, app_handle: AppHandle) -> Self {
+        Self {
+            label: window.label().to_string(),
+            window,
+            app_handle,
+            frame_nr: 0,
+            cpu_usage: None,
+            commands: Vec::new(),
+        }
+    }
+
+    /// Label of the Tauri window.
+    pub fn label(&self) -> &str {
+        &self.label
+    }
+
+    /// The Tauri window egui is drawing in.
+    ///
+    /// Calls that change the window or its egui state (e.g. closing it or
+    /// [`crate::AppHandleExt::stop_egui_for_window`]) should go through
+    /// [`Self::queue_command`] instead, as the plugin is busy rendering this frame.
+    pub fn window(&self) -> &tauri::Window {
+        &self.window
+    }
+
+    /// Handle to the Tauri app, e.g. to emit events or look up managed state.
+    pub fn app_handle(&self) -> &AppHandle {
+        &self.app_handle
+    }
+
+    /// How many frames have been rendered before this one.
+    pub fn frame_nr(&self) -> u64 {
+        self.frame_nr
+    }
+
+    /// Seconds of CPU time spent on the previous frame, from input to GPU submission.
+    /// `None` on the first frame.
+    pub fn cpu_usage(&self) -> Option<f32> {
+        self.cpu_usage
+    }
+
+    /// Runs `command` with the window once this frame has been rendered and
+    /// the plugin has released its window map.
+    pub fn queue_command(&mut self, command: impl FnOnce(&tauri::Window) + 'static) {
+        self.commands.push(Box::new(command));
+    }
+
+    /// Closes the window after this frame.
+    pub fn close(&mut self) {
+        self.queue_command(|window| {
+            if let Err(e) = window.close() {
+                eprintln!("Failed to close window {}: {}", window.label(), e);
+            }
+        });
+    }
+
+    /// Records the frame that just finished, returning the commands it queued.
+    pub(crate) fn finish(&mut self, cpu_usage: f32) -> Vec<WindowCommand> {
+        self.frame_nr += 1;
+        self.cpu_usage = Some(cpu_usage);
+        std::mem::take(&mut self.commands)
+    }
+}
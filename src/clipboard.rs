@@ -0,0 +1,152 @@
+use std::sync::{Arc, Mutex};
+
+/// A clipboard backend used by egui windows for copy, cut and paste.
+///
+/// The plugin uses [`SystemClipboard`] by default, but any implementation can be
+/// passed to [`crate::Builder::clipboard`] (e.g. [`MemoryClipboard`] in tests).
+pub trait Clipboard: Send {
+    /// Returns the current text content of the clipboard, if any.
+    fn get_text(&mut self) -> Option<String>;
+
+    /// Replaces the clipboard content with the given text.
+    fn set_text(&mut self, text: &str);
+
+    /// Replaces the clipboard content with the given RGBA image.
+    fn set_image(&mut self, image: &egui::ColorImage);
+}
+
+/// The paste event for the clipboard's text, with Windows line endings normalized.
+/// `None` if there is nothing to paste.
+pub(crate) fn paste_event(clipboard: &mut dyn Clipboard) -> Option<egui::Event> {
+    let text = clipboard.get_text()?.replace("\r\n", "\n");
+    (!text.is_empty()).then_some(egui::Event::Paste(text))
+}
+
+/// The OS clipboard, backed by `arboard`.
+pub struct SystemClipboard {
+    arboard: Option<arboard::Clipboard>,
+}
+
+impl SystemClipboard {
+    pub fn new() -> Self {
+        let arboard = match arboard::Clipboard::new() {
+            Ok(clipboard) => Some(clipboard),
+            Err(e) => {
+                eprintln!("Failed to initialize system clipboard: {}", e);
+                None
+            }
+        };
+
+        Self { arboard }
+    }
+}
+
+impl Default for SystemClipboard {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Clipboard for SystemClipboard {
+    fn get_text(&mut self) -> Option<String> {
+        let clipboard = self.arboard.as_mut()?;
+        match clipboard.get_text() {
+            Ok(text) => Some(text),
+            Err(arboard::Error::ContentNotAvailable) => None,
+            Err(e) => {
+                eprintln!("Failed to read clipboard text: {}", e);
+                None
+            }
+        }
+    }
+
+    fn set_text(&mut self, text: &str) {
+        if let Some(clipboard) = self.arboard.as_mut() {
+            if let Err(e) = clipboard.set_text(text) {
+                eprintln!("Failed to set clipboard text: {}", e);
+            }
+        }
+    }
+
+    fn set_image(&mut self, image: &egui::ColorImage) {
+        if let Some(clipboard) = self.arboard.as_mut() {
+            let image_data = arboard::ImageData {
+                width: image.width(),
+                height: image.height(),
+                bytes: std::borrow::Cow::Borrowed(image.as_raw()),
+            };
+            if let Err(e) = clipboard.set_image(image_data) {
+                eprintln!("Failed to set clipboard image: {}", e);
+            }
+        }
+    }
+}
+
+/// An in-process clipboard that never touches the OS.
+///
+/// Clones share the same contents, so a test can keep one handle and inspect
+/// what egui copied through the other.
+#[derive(Clone, Default)]
+pub struct MemoryClipboard {
+    contents: Arc<Mutex<MemoryContents>>,
+}
+
+#[derive(Default)]
+struct MemoryContents {
+    text: Option<String>,
+    image: Option<egui::ColorImage>,
+}
+
+impl MemoryClipboard {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The text currently stored in this clipboard.
+    pub fn text(&self) -> Option<String> {
+        self.contents.lock().unwrap().text.clone()
+    }
+
+    /// The image currently stored in this clipboard.
+    pub fn image(&self) -> Option<egui::ColorImage> {
+        self.contents.lock().unwrap().image.clone()
+    }
+}
+
+impl Clipboard for MemoryClipboard {
+    fn get_text(&mut self) -> Option<String> {
+        self.text()
+    }
+
+    fn set_text(&mut self, text: &str) {
+        let mut contents = self.contents.lock().unwrap();
+        contents.text = Some(text.to_string());
+        contents.image = None;
+    }
+
+    fn set_image(&mut self, image: &egui::ColorImage) {
+        let mut contents = self.contents.lock().unwrap();
+        contents.image = Some(image.clone());
+        contents.text = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pasted_text_has_unix_line_endings() {
+        let mut clipboard = MemoryClipboard::new();
+        assert_eq!(paste_event(&mut clipboard), None);
+
+        clipboard.set_text("");
+        assert_eq!(paste_event(&mut clipboard), None);
+
+        clipboard.set_text("one\r\ntwo\n");
+        assert_eq!(
+            paste_event(&mut clipboard),
+            Some(egui::Event::Paste("one\ntwo\n".to_string()))
+        );
+    }
+}
@@ -0,0 +1,230 @@
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use anyhow::Error;
+use egui_wgpu::wgpu;
+
+use tauri_runtime::UserEvent;
+use tauri_runtime_wry::tao::event_loop::EventLoopProxy;
+use tauri_runtime_wry::Message;
+
+use crate::plugin::{egui_windows, EguiWindow};
+use crate::renderer::{GpuContext, TargetFormat};
+
+type Task = Box<dyn FnOnce() + Send>;
+
+/// Posts closures to the event loop through the tao `EventLoopProxy`.
+///
+/// Unlike `AppHandle::run_on_main_thread`, tasks are always queued, even when sent
+/// from the main thread, so they never run while the plugin holds the window map.
+#[derive(Clone)]
+pub(crate) struct TaskSender {
+    send: Arc<dyn Fn(Task) -> bool + Send + Sync>,
+}
+
+impl TaskSender {
+    pub fn new<T: UserEvent>(proxy: EventLoopProxy<Message<T>>) -> Self {
+        let proxy = Mutex::new(proxy);
+        Self {
+            send: Arc::new(move |task| {
+                proxy
+                    .lock()
+                    .unwrap()
+                    .send_event(Message::Task(task))
+                    .is_ok()
+            }),
+        }
+    }
+
+    pub fn send(&self, task: impl FnOnce() + Send + 'static) {
+        if !(self.send)(Box::new(task)) {
+            eprintln!("Failed to post task to the event loop");
+        }
+    }
+}
+
+/// A handle to an egui window that can be used from any thread,
+/// e.g. to repaint once a background task has loaded new data.
+///
+/// Returned by [`crate::AppHandleExt::start_egui_for_window`].
+/// Everything that touches the window is posted to the UI thread.
+#[derive(Clone)]
+pub struct EguiHandle {
+    label: String,
+    context: egui::Context,
+    tasks: TaskSender,
+    gpu: GpuContext,
+    target_format: TargetFormat,
+    /// The id the next native texture gets, handed out here since registering
+    /// only happens once the UI thread gets to it
+    next_texture_id: Arc<Mutex<u64>>,
+}
+
+impl EguiHandle {
+    pub(crate) fn new(
+        label: String,
+        context: egui::Context,
+        tasks: TaskSender,
+        gpu: GpuContext,
+        target_format: TargetFormat,
+    ) -> Self {
+        Self {
+            label,
+            context,
+            tasks,
+            gpu,
+            target_format,
+            next_texture_id: Arc::default(),
+        }
+    }
+
+    /// Runs `f` on the UI thread with the window, unless egui was stopped in it since.
+    fn with_window(&self, f: impl FnOnce(&mut EguiWindow) + Send + 'static) {
+        let label = self.label.clone();
+        let context = self.context.clone();
+        self.tasks.send(move || {
+            // Tasks run on the event loop thread, where the egui windows live
+            let egui_windows = egui_windows();
+            let mut windows = egui_windows.borrow_mut();
+            if let Some(egui_win) = windows
+                .get_mut(&label)
+                .filter(|egui_win| *egui_win.context() == context)
+            {
+                f(egui_win);
+            }
+        });
+    }
+
+    /// Label of the Tauri window.
+    pub fn label(&self) -> &str {
+        &self.label
+    }
+
+    /// Repaint the window as soon as possible.
+    pub fn request_repaint(&self) {
+        self.context.request_repaint();
+    }
+
+    /// Repaint the window after `delay`, unless a repaint is already due sooner.
+    pub fn request_repaint_after(&self, delay: Duration) {
+        self.context.request_repaint_after(delay);
+    }
+
+    /// Feeds an input event to the window's next frame, as if it came from the OS.
+    pub fn inject_event(&self, event: egui::Event) {
+        self.with_window(move |egui_win| egui_win.inject_event(event));
+    }
+
+    /// The wgpu device the window renders with. Textures registered with
+    /// [`Self::register_native_texture`] must be created on it.
+    pub fn gpu_context(&self) -> &GpuContext {
+        &self.gpu
+    }
+
+    /// The formats pipelines drawn in the window's [`egui_wgpu::Callback`]s have to use.
+    pub fn target_format(&self) -> TargetFormat {
+        self.target_format
+    }
+
+    /// Runs `f` on the UI thread with the resources passed to the window's paint
+    /// callbacks, e.g. to insert their pipelines and buffers.
+    ///
+    /// These belong to the root window, child viewports draw with renderers of their own.
+    pub fn with_callback_resources(
+        &self,
+        f: impl FnOnce(&mut egui_wgpu::CallbackResources) + Send + 'static,
+    ) {
+        self.with_window(move |egui_win| egui_win.with_callback_resources(f));
+    }
+
+    /// Makes a texture usable in egui widgets, e.g. `ui.image((id, size))`.
+    ///
+    /// The texture should be `Rgba8UnormSrgb` or `Rgba8Unorm` and have the `TEXTURE_BINDING`
+    /// usage. The id works in the window and all of its child viewports.
+    /// Call [`Self::request_repaint`] after writing into it to show the new contents.
+    pub fn register_native_texture(
+        &self,
+        texture: &wgpu::TextureView,
+        filter: wgpu::FilterMode,
+    ) -> egui::TextureId {
+        let mut next_id = self.next_texture_id.lock().unwrap();
+        let id = egui::TextureId::User(*next_id);
+        *next_id += 1;
+
+        // Posted while holding the lock, so the UI thread registers them in id order
+        let texture = texture.clone();
+        self.with_window(move |egui_win| {
+            let registered = egui_win.native_textures().register(&texture, filter);
+            debug_assert_eq!(registered, id);
+        });
+        id
+    }
+
+    /// Points a registered texture at a new view, e.g. after resizing it.
+    ///
+    /// Fails for ids this handle didn't hand out, updating a freed texture is only logged.
+    pub fn update_native_texture(
+        &self,
+        id: egui::TextureId,
+        texture: &wgpu::TextureView,
+        filter: wgpu::FilterMode,
+    ) -> Result<(), Error> {
+        if !self.is_native_texture(id) {
+            return Err(Error::msg(format!(
+                "{:?} isn't a registered native texture",
+                id
+            )));
+        }
+
+        let texture = texture.clone();
+        self.with_window(move |egui_win| {
+            if let Err(err) = egui_win.native_textures().update(id, &texture, filter) {
+                eprintln!("Failed to update native texture: {}", err);
+            }
+        });
+        self.context.request_repaint();
+        Ok(())
+    }
+
+    /// Frees a texture registered with [`Self::register_native_texture`].
+    /// egui won't draw it anymore, the texture itself is still owned by the caller.
+    pub fn free_native_texture(&self, id: egui::TextureId) {
+        self.with_window(move |egui_win| egui_win.native_textures().free(id));
+        self.context.request_repaint();
+    }
+
+    fn is_native_texture(&self, id: egui::TextureId) -> bool {
+        match id {
+            egui::TextureId::User(index) => index < *self.next_texture_id.lock().unwrap(),
+            egui::TextureId::Managed(_) => false,
+        }
+    }
+
+    /// Runs `f` on the UI thread with the window's egui context.
+    ///
+    /// `f` is sent to the event loop thread, so it can't capture thread-bound data:
+    ///
+    /// ```compile_fail,E0277
+    /// fn run(handle: &tauri_plugin_egui::EguiHandle) {
+    ///     let data = std::rc::Rc::new(42);
+    ///     handle.run_on_ui_thread(move |_ctx| println!("{}", data));
+    /// }
+    /// ```
+    pub fn run_on_ui_thread(&self, f: impl FnOnce(&egui::Context) + Send + 'static) {
+        let context = self.context.clone();
+        self.tasks.send(move || f(&context));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_send_sync<T: Send + Sync>() {}
+
+    #[test]
+    fn egui_handle_can_be_shared_across_threads() {
+        assert_send_sync::<EguiHandle>();
+        assert_send_sync::<TaskSender>();
+    }
+}
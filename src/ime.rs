@@ -0,0 +1,83 @@
+//! Native IME helpers.
+//!
+//! tao doesn't expose IME control for windows created by Tauri, so these talk to
+//! the platform directly where possible, which is only the case on Windows.
+//!
+//! On macOS and Linux the input context belongs to tao's view, which Tauri keeps
+//! to itself. The IME stays enabled for the whole window, and its candidate window
+//! isn't moved to egui's text cursor: tao places it from `set_ime_position`, which
+//! can't be reached for Tauri windows. There these functions do nothing.
+
+#[cfg(windows)]
+use windows_sys::Win32::{
+    Foundation::{HWND, POINT, RECT},
+    UI::Input::Ime::{
+        ImmAssociateContextEx, ImmGetContext, ImmReleaseContext, ImmSetCandidateWindow,
+        ImmSetCompositionWindow, CANDIDATEFORM, CFS_EXCLUDE, CFS_POINT, COMPOSITIONFORM,
+        IACE_DEFAULT,
+    },
+    UI::WindowsAndMessaging::{GetSystemMetrics, SM_IMMENABLED},
+};
+
+#[cfg(windows)]
+fn ime_hwnd(window: &tauri::Window) -> Option<HWND> {
+    if unsafe { GetSystemMetrics(SM_IMMENABLED) } == 0 {
+        return None;
+    }
+    window.hwnd().ok().map(|hwnd| hwnd.0 as HWND)
+}
+
+/// Enables or disables the IME for a window, only affecting the window itself
+/// so that child webviews keep their own input context.
+#[cfg(windows)]
+pub(crate) fn set_ime_allowed(window: &tauri::Window, allowed: bool) {
+    if let Some(hwnd) = ime_hwnd(window) {
+        let flags = if allowed { IACE_DEFAULT } else { 0 };
+        unsafe { ImmAssociateContextEx(hwnd, std::ptr::null_mut(), flags) };
+    }
+}
+
+/// Places the composition and candidate windows next to `cursor_rect`,
+/// given in physical pixels relative to the window.
+#[cfg(windows)]
+pub(crate) fn set_ime_cursor_area(window: &tauri::Window, cursor_rect: egui::Rect) {
+    let Some(hwnd) = ime_hwnd(window) else {
+        return;
+    };
+
+    let x = cursor_rect.min.x.round() as i32;
+    let y = cursor_rect.min.y.round() as i32;
+    let area = RECT {
+        left: x,
+        top: y,
+        right: cursor_rect.max.x.round() as i32,
+        bottom: cursor_rect.max.y.round() as i32,
+    };
+    let candidate_form = CANDIDATEFORM {
+        dwIndex: 0,
+        dwStyle: CFS_EXCLUDE,
+        ptCurrentPos: POINT { x, y },
+        rcArea: area,
+    };
+    let composition_form = COMPOSITIONFORM {
+        dwStyle: CFS_POINT,
+        ptCurrentPos: POINT { x, y: area.bottom },
+        rcArea: area,
+    };
+
+    unsafe {
+        let himc = ImmGetContext(hwnd);
+        if himc.is_null() {
+            return;
+        }
+        ImmSetCompositionWindow(himc, &composition_form);
+        ImmSetCandidateWindow(himc, &candidate_form);
+        ImmReleaseContext(hwnd, himc);
+    }
+}
+
+#[cfg(not(windows))]
+pub(crate) fn set_ime_allowed(_window: &tauri::Window, _allowed: bool) {}
+
+#[cfg(not(windows))]
+pub(crate) fn set_ime_cursor_area(_window: &tauri::Window, _cursor_rect: egui::Rect) {}
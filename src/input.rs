@@ -0,0 +1,583 @@
+use std::time::Instant;
+
+use tauri::PhysicalSize;
+use tauri_runtime_wry::tao::event::{
+    ElementState, KeyEvent, MouseButton, MouseScrollDelta, WindowEvent as TaoWindowEvent,
+};
+use tauri_runtime_wry::tao::keyboard::{Key, KeyCode};
+
+use crate::clipboard::{self, Clipboard};
+use crate::keyboard::{
+    is_copy_command, is_cut_command, is_paste_command, translate_logical_key,
+    translate_physical_key,
+};
+use crate::region::PhysicalRect;
+
+/// Collects the window events of an egui window into the `RawInput` of its next frame.
+pub(crate) struct EguiInput {
+    egui_input: egui::RawInput,
+    start_time: Instant,
+    /// Size of the window's surface, in physical pixels
+    pub size: PhysicalSize<u32>,
+    /// The part of the window egui draws into, the whole window if `None`
+    pub region: Option<PhysicalRect>,
+    /// The window's DPI scale, as reported by the OS
+    pub native_pixels_per_point: f32,
+    /// egui's own zoom on top of the DPI scale, e.g. from `Ctrl +`
+    pub zoom_factor: f32,
+    /// The viewport shown in the window, `ROOT` unless it's a child viewport
+    pub viewport_id: egui::ViewportId,
+    /// What egui wanted in the last frame, see [`InputWants`]
+    pub wants: InputWants,
+    pointer_pos: Option<egui::Pos2>,
+    modifiers: egui::Modifiers,
+    /// Text the previous event typed, which the IME may report a second time
+    key_echo: KeyEcho,
+}
+
+/// Text typed by the previous window event. tao reports plain typing both as a key
+/// press and as IME text, one right after the other, so only that pair is merged.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+enum KeyEcho {
+    #[default]
+    None,
+    /// A key press typed this text, on Linux the input context echoes it next
+    KeyText(String),
+    /// The IME committed this text, on macOS the key press that typed it follows
+    ImeCommit(String),
+}
+
+/// The parts of a tao `KeyEvent` egui needs, which unlike `KeyEvent` can be built outside of tao.
+#[derive(Debug, Clone)]
+struct KeyInput {
+    logical_key: Key<'static>,
+    physical_key: KeyCode,
+    text: Option<&'static str>,
+    state: ElementState,
+    repeat: bool,
+}
+
+impl From<&KeyEvent> for KeyInput {
+    fn from(event: &KeyEvent) -> Self {
+        Self {
+            logical_key: event.logical_key.clone(),
+            physical_key: event.physical_key,
+            text: event.text,
+            state: event.state,
+            repeat: event.repeat,
+        }
+    }
+}
+
+/// tao only reports the committed text of a composition, without the preedit text
+/// or its start and end. egui's `TextEdit` only accepts a commit for a composition
+/// it saw start, so wrap it in one.
+fn ime_commit_events(text: &str) -> [egui::Event; 3] {
+    [
+        egui::Event::Ime(egui::ImeEvent::Enabled),
+        egui::Event::Ime(egui::ImeEvent::Commit(text.to_string())),
+        egui::Event::Ime(egui::ImeEvent::Disabled),
+    ]
+}
+
+impl EguiInput {
+    pub fn new(size: PhysicalSize<u32>, native_pixels_per_point: f32) -> Self {
+        Self {
+            egui_input: egui::RawInput::default(),
+            start_time: Instant::now(),
+            size,
+            region: None,
+            native_pixels_per_point,
+            zoom_factor: 1.0,
+            viewport_id: egui::ViewportId::ROOT,
+            wants: InputWants::default(),
+            pointer_pos: None,
+            modifiers: egui::Modifiers::default(),
+            key_echo: KeyEcho::None,
+        }
+    }
+
+    /// Queues an event for the next frame, bypassing the window event translation
+    pub fn push_event(&mut self, event: egui::Event) {
+        self.egui_input.events.push(event);
+    }
+
+    /// Tells egui the user wants to close the viewport, e.g. with the window's close button
+    pub fn request_close(&mut self) {
+        self.egui_input
+            .viewports
+            .entry(self.viewport_id)
+            .or_default()
+            .events
+            .push(egui::ViewportEvent::Close);
+    }
+
+    /// Physical pixels per egui point
+    pub fn pixels_per_point(&self) -> f32 {
+        self.native_pixels_per_point * self.zoom_factor
+    }
+
+    /// The part of the window egui draws into, in physical pixels.
+    pub fn region(&self) -> PhysicalRect {
+        self.region
+            .unwrap_or(PhysicalRect::new(0, 0, self.size.width, self.size.height))
+    }
+
+    /// Where egui's origin is in the window, in points.
+    pub fn region_offset(&self) -> egui::Vec2 {
+        let position = self.region().position;
+        egui::vec2(position.x as f32, position.y as f32) / self.pixels_per_point()
+    }
+
+    pub fn handle_event(&mut self, event: &TaoWindowEvent, clipboard: &mut dyn Clipboard) -> bool {
+        if let TaoWindowEvent::KeyboardInput { event, .. } = event {
+            return self.handle_key(&KeyInput::from(event), clipboard);
+        }
+
+        // Only the event right after the one that typed text can be its echo
+        let key_echo = std::mem::take(&mut self.key_echo);
+
+        match event {
+            TaoWindowEvent::Resized(size) => {
+                self.size = PhysicalSize::new(size.width, size.height);
+                false
+            }
+            TaoWindowEvent::ScaleFactorChanged {
+                scale_factor,
+                new_inner_size,
+            } => {
+                // Positions of events that arrive later in this frame are in
+                // the new scale, egui picks it up from the viewport info.
+                self.native_pixels_per_point = *scale_factor as f32;
+                self.size = PhysicalSize::new(new_inner_size.width, new_inner_size.height);
+                false
+            }
+            TaoWindowEvent::CursorMoved { position, .. } => {
+                // Relative to the region, which is all egui knows of
+                let origin = self.region().position;
+                let pos = egui::Pos2::new(
+                    (position.x - origin.x as f64) as f32 / self.pixels_per_point(),
+                    (position.y - origin.y as f64) as f32 / self.pixels_per_point(),
+                );
+                self.pointer_pos = Some(pos);
+                self.egui_input.events.push(egui::Event::PointerMoved(pos));
+                true
+            }
+            TaoWindowEvent::ModifiersChanged(modifiers) => {
+                self.modifiers = egui::Modifiers {
+                    alt: modifiers.alt_key(),
+                    ctrl: modifiers.control_key(),
+                    shift: modifiers.shift_key(),
+                    #[cfg(target_os = "macos")]
+                    mac_cmd: modifiers.super_key(),
+                    #[cfg(target_os = "macos")]
+                    command: modifiers.super_key(),
+                    #[cfg(not(target_os = "macos"))]
+                    mac_cmd: false,
+                    #[cfg(not(target_os = "macos"))]
+                    command: modifiers.control_key(),
+                };
+                self.egui_input.modifiers = self.modifiers;
+                true
+            }
+            TaoWindowEvent::MouseInput { state, button, .. } => {
+                let pressed = *state == ElementState::Pressed;
+                let button = match button {
+                    MouseButton::Left => egui::PointerButton::Primary,
+                    MouseButton::Right => egui::PointerButton::Secondary,
+                    MouseButton::Middle => egui::PointerButton::Middle,
+                    _ => return false,
+                };
+
+                // Use current pointer position, or default to (0,0) if not set
+                let pos = self.pointer_pos.unwrap_or(egui::Pos2::ZERO);
+
+                self.egui_input.events.push(egui::Event::PointerButton {
+                    pos,
+                    button,
+                    pressed,
+                    modifiers: self.modifiers,
+                });
+                true
+            }
+            TaoWindowEvent::MouseWheel { delta, .. } => {
+                let (x, y) = match delta {
+                    MouseScrollDelta::LineDelta(x, y) => (*x * 60.0, *y * 60.0),
+                    MouseScrollDelta::PixelDelta(pos) => (
+                        pos.x as f32 / self.pixels_per_point(),
+                        pos.y as f32 / self.pixels_per_point(),
+                    ),
+                    _ => (0.0, 0.0),
+                };
+                self.egui_input.events.push(egui::Event::MouseWheel {
+                    unit: egui::MouseWheelUnit::Point,
+                    delta: egui::Vec2::new(x, y),
+                    modifiers: self.modifiers,
+                });
+                true
+            }
+            TaoWindowEvent::ReceivedImeText(text) => {
+                if text.is_empty() || key_echo == KeyEcho::KeyText(text.clone()) {
+                    return true;
+                }
+
+                self.egui_input.events.extend(ime_commit_events(text));
+                self.key_echo = KeyEcho::ImeCommit(text.clone());
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn handle_key(&mut self, event: &KeyInput, clipboard: &mut dyn Clipboard) -> bool {
+        let key_echo = std::mem::take(&mut self.key_echo);
+        let pressed = event.state == ElementState::Pressed;
+        let mut handled = false;
+
+        // The logical key respects the keyboard layout, the physical key is
+        // a fallback for layouts without latin characters (e.g. Ctrl+C on Cyrillic)
+        let logical_key = translate_logical_key(&event.logical_key);
+        let physical_key = translate_physical_key(&event.physical_key);
+
+        // Clipboard shortcuts are turned into egui's dedicated events,
+        // `TextEdit` doesn't act on the raw key presses for these.
+        let active_key = logical_key.or(physical_key).filter(|_| pressed);
+        if let Some(key) = active_key {
+            if is_cut_command(self.modifiers, key) {
+                self.egui_input.events.push(egui::Event::Cut);
+                return true;
+            } else if is_copy_command(self.modifiers, key) {
+                self.egui_input.events.push(egui::Event::Copy);
+                return true;
+            } else if is_paste_command(self.modifiers, key) {
+                if let Some(event) = clipboard::paste_event(clipboard) {
+                    self.egui_input.events.push(event);
+                }
+                return true;
+            }
+        }
+
+        // Handle text input from the text field
+        if pressed {
+            if let Some(text) = &event.text {
+                if !text.is_empty() {
+                    // Filter out control characters
+                    let filtered: String = text
+                        .chars()
+                        .filter(|c| !c.is_control() || *c == '\t' || *c == '\n' || *c == '\r')
+                        .collect();
+
+                    if !filtered.is_empty() {
+                        // On macOS plain typing arrives as an IME commit right before
+                        // the key event, so turn that commit back into regular text.
+                        // If a frame already took the commit, the text was typed with it.
+                        let echoed = key_echo == KeyEcho::ImeCommit(filtered.clone());
+                        let commit = ime_commit_events(&filtered);
+                        let events = &mut self.egui_input.events;
+                        if !echoed || events.ends_with(&commit) {
+                            if echoed {
+                                events.truncate(events.len() - commit.len());
+                            }
+                            events.push(egui::Event::Text(filtered.clone()));
+                        }
+                        self.key_echo = KeyEcho::KeyText(filtered);
+                        handled = true;
+                    }
+                }
+            }
+        }
+
+        // Handle key events (logical key first, then physical key fallback)
+        if let Some(key) = logical_key.or(physical_key) {
+            self.egui_input.events.push(egui::Event::Key {
+                key,
+                physical_key,
+                pressed,
+                repeat: event.repeat,
+                modifiers: self.modifiers,
+            });
+            handled = true;
+        }
+
+        handled
+    }
+
+    pub fn take_egui_input(&mut self) -> egui::RawInput {
+        let mut input = std::mem::take(&mut self.egui_input);
+        input.viewport_id = self.viewport_id;
+        input.time = Some(self.start_time.elapsed().as_secs_f64());
+        let size = self.region().size;
+        input.screen_rect = Some(egui::Rect::from_min_size(
+            egui::Pos2::ZERO,
+            egui::Vec2::new(
+                size.width as f32 / self.pixels_per_point(),
+                size.height as f32 / self.pixels_per_point(),
+            ),
+        ));
+        input
+            .viewports
+            .entry(self.viewport_id)
+            .or_default()
+            .native_pixels_per_point = Some(self.native_pixels_per_point);
+        input
+    }
+}
+
+/// What egui wanted from the input in its last frame.
+///
+/// Window events egui doesn't want are still fed to it, but left for the
+/// webview and other plugins rather than reported as consumed.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub(crate) struct InputWants {
+    /// The pointer is over egui or egui is using it, e.g. to drag a slider
+    pub pointer: bool,
+    /// A widget has keyboard focus, e.g. a text field
+    pub keyboard: bool,
+}
+
+impl InputWants {
+    /// Call between frames, egui answers for the frame that just ran.
+    pub fn of(ctx: &egui::Context) -> Self {
+        Self {
+            pointer: ctx.is_pointer_over_area() || ctx.wants_pointer_input(),
+            keyboard: ctx.wants_keyboard_input(),
+        }
+    }
+
+    /// Whether egui should consume the event rather than let it through.
+    /// Only input events egui wanted are consumed, the window's own (resizing,
+    /// moving, focus, ...) always reach Tauri and the app.
+    pub fn event(&self, event: &TaoWindowEvent) -> bool {
+        match event {
+            TaoWindowEvent::CursorMoved { .. }
+            | TaoWindowEvent::MouseInput { .. }
+            | TaoWindowEvent::MouseWheel { .. } => self.pointer,
+            TaoWindowEvent::KeyboardInput { .. }
+            | TaoWindowEvent::ReceivedImeText(_)
+            | TaoWindowEvent::ModifiersChanged(_) => self.keyboard,
+            _ => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clipboard::MemoryClipboard;
+    use tauri_runtime_wry::tao::dpi::{PhysicalPosition, PhysicalSize as TaoPhysicalSize};
+    use tauri_runtime_wry::tao::event::DeviceId;
+    use tauri_runtime_wry::tao::keyboard::ModifiersState;
+
+    fn scale_factor_changed(input: &mut EguiInput, scale_factor: f64, width: u32, height: u32) {
+        let mut new_inner_size = TaoPhysicalSize::new(width, height);
+        let event = TaoWindowEvent::ScaleFactorChanged {
+            scale_factor,
+            new_inner_size: &mut new_inner_size,
+        };
+        input.handle_event(&event, &mut MemoryClipboard::new());
+    }
+
+    #[allow(deprecated)]
+    fn cursor_moved_event(x: f64, y: f64) -> TaoWindowEvent<'static> {
+        TaoWindowEvent::CursorMoved {
+            device_id: unsafe { DeviceId::dummy() },
+            position: PhysicalPosition::new(x, y),
+            modifiers: ModifiersState::empty(),
+        }
+    }
+
+    fn cursor_moved(input: &mut EguiInput, x: f64, y: f64) {
+        input.handle_event(&cursor_moved_event(x, y), &mut MemoryClipboard::new());
+    }
+
+    /// What egui wants after a few frames of an area at the top left, with the pointer at `pos`.
+    fn wants_with_pointer_at(pos: egui::Pos2) -> InputWants {
+        let ctx = egui::Context::default();
+        for _ in 0..2 {
+            let raw_input = egui::RawInput {
+                screen_rect: Some(egui::Rect::from_min_size(
+                    egui::Pos2::ZERO,
+                    egui::vec2(400.0, 300.0),
+                )),
+                events: vec![egui::Event::PointerMoved(pos)],
+                ..Default::default()
+            };
+            let _ = ctx.run(raw_input, |ctx| {
+                egui::Area::new(egui::Id::new("area"))
+                    .fixed_pos(egui::Pos2::ZERO)
+                    .show(ctx, |ui| ui.allocate_space(egui::vec2(100.0, 100.0)));
+            });
+        }
+        InputWants::of(&ctx)
+    }
+
+    fn native_pixels_per_point(raw_input: &egui::RawInput) -> Option<f32> {
+        raw_input.viewports[&egui::ViewportId::ROOT].native_pixels_per_point
+    }
+
+    #[test]
+    fn scale_factor_change_updates_screen_rect_and_ppp() {
+        let mut input = EguiInput::new(PhysicalSize::new(800, 600), 1.0);
+        let raw_input = input.take_egui_input();
+        assert_eq!(native_pixels_per_point(&raw_input), Some(1.0));
+        assert_eq!(
+            raw_input.screen_rect.unwrap().size(),
+            egui::vec2(800.0, 600.0)
+        );
+
+        // Dragged onto a 2x monitor, the OS doubles the physical size
+        scale_factor_changed(&mut input, 2.0, 1600, 1200);
+        assert_eq!(input.size, PhysicalSize::new(1600, 1200));
+
+        let raw_input = input.take_egui_input();
+        assert_eq!(native_pixels_per_point(&raw_input), Some(2.0));
+        assert_eq!(
+            raw_input.screen_rect.unwrap().size(),
+            egui::vec2(800.0, 600.0)
+        );
+    }
+
+    #[test]
+    fn pointer_positions_follow_the_new_scale() {
+        let mut input = EguiInput::new(PhysicalSize::new(800, 600), 1.0);
+        cursor_moved(&mut input, 100.0, 50.0);
+
+        scale_factor_changed(&mut input, 1.5, 1200, 900);
+        cursor_moved(&mut input, 300.0, 150.0);
+
+        let raw_input = input.take_egui_input();
+        assert_eq!(
+            raw_input.events,
+            vec![
+                egui::Event::PointerMoved(egui::pos2(100.0, 50.0)),
+                egui::Event::PointerMoved(egui::pos2(200.0, 100.0)),
+            ]
+        );
+    }
+
+    #[test]
+    fn zoom_factor_applies_on_top_of_the_scale_factor() {
+        let mut input = EguiInput::new(PhysicalSize::new(800, 600), 1.0);
+        scale_factor_changed(&mut input, 2.0, 1600, 1200);
+        input.zoom_factor = 2.0;
+
+        cursor_moved(&mut input, 400.0, 400.0);
+
+        let raw_input = input.take_egui_input();
+        assert_eq!(native_pixels_per_point(&raw_input), Some(2.0));
+        assert_eq!(
+            raw_input.screen_rect.unwrap().size(),
+            egui::vec2(400.0, 300.0)
+        );
+        assert_eq!(
+            raw_input.events,
+            vec![egui::Event::PointerMoved(egui::pos2(100.0, 100.0))]
+        );
+    }
+
+    #[test]
+    fn pointer_events_go_to_egui_only_over_its_areas() {
+        let over_area = wants_with_pointer_at(egui::pos2(50.0, 50.0));
+        assert!(over_area.pointer);
+        assert!(over_area.event(&cursor_moved_event(60.0, 60.0)));
+
+        let elsewhere = wants_with_pointer_at(egui::pos2(200.0, 200.0));
+        assert!(!elsewhere.pointer);
+        assert!(!elsewhere.event(&cursor_moved_event(210.0, 210.0)));
+
+        // Nothing has keyboard focus either way
+        assert!(!over_area.keyboard);
+
+        // The window's own events always get through
+        let resized = TaoWindowEvent::Resized(TaoPhysicalSize::new(800, 600));
+        assert!(!over_area.event(&resized));
+    }
+
+    #[test]
+    fn regions_move_the_origin_and_shrink_the_screen() {
+        let mut input = EguiInput::new(PhysicalSize::new(800, 600), 2.0);
+        input.region = Some(PhysicalRect::new(200, 100, 400, 300));
+
+        cursor_moved(&mut input, 300.0, 300.0);
+
+        let raw_input = input.take_egui_input();
+        assert_eq!(
+            raw_input.screen_rect,
+            Some(egui::Rect::from_min_size(
+                egui::Pos2::ZERO,
+                egui::vec2(200.0, 150.0)
+            ))
+        );
+        assert_eq!(
+            raw_input.events,
+            vec![egui::Event::PointerMoved(egui::pos2(50.0, 100.0))]
+        );
+        assert_eq!(input.region_offset(), egui::vec2(100.0, 50.0));
+    }
+
+    fn ime_text(input: &mut EguiInput, text: &str) {
+        let event = TaoWindowEvent::ReceivedImeText(text.to_string());
+        input.handle_event(&event, &mut MemoryClipboard::new());
+    }
+
+    /// Presses the A key, typing `a`.
+    fn type_a(input: &mut EguiInput) {
+        let key = KeyInput {
+            logical_key: Key::Character("a"),
+            physical_key: KeyCode::KeyA,
+            text: Some("a"),
+            state: ElementState::Pressed,
+            repeat: false,
+        };
+        input.handle_key(&key, &mut MemoryClipboard::new());
+    }
+
+    /// The events that type text, leaving out key presses and pointer moves.
+    fn typed(input: &mut EguiInput) -> Vec<egui::Event> {
+        let events = input.take_egui_input().events;
+        events
+            .into_iter()
+            .filter(|event| matches!(event, egui::Event::Text(_) | egui::Event::Ime(_)))
+            .collect()
+    }
+
+    #[test]
+    fn ime_echo_of_the_previous_key_press_is_dropped() {
+        let mut input = EguiInput::new(PhysicalSize::new(800, 600), 1.0);
+        type_a(&mut input);
+        ime_text(&mut input, "a");
+        assert_eq!(typed(&mut input), vec![egui::Event::Text("a".to_string())]);
+    }
+
+    #[test]
+    fn ime_commit_before_the_key_press_becomes_text() {
+        let mut input = EguiInput::new(PhysicalSize::new(800, 600), 1.0);
+        ime_text(&mut input, "a");
+        type_a(&mut input);
+        assert_eq!(typed(&mut input), vec![egui::Event::Text("a".to_string())]);
+    }
+
+    #[test]
+    fn ime_commit_taken_by_a_frame_isnt_typed_again() {
+        let mut input = EguiInput::new(PhysicalSize::new(800, 600), 1.0);
+        ime_text(&mut input, "a");
+        assert_eq!(typed(&mut input), ime_commit_events("a").to_vec());
+
+        type_a(&mut input);
+        assert_eq!(typed(&mut input), Vec::new());
+    }
+
+    #[test]
+    fn ime_commits_equal_to_earlier_typing_are_kept() {
+        let mut input = EguiInput::new(PhysicalSize::new(800, 600), 1.0);
+        type_a(&mut input);
+        cursor_moved(&mut input, 10.0, 10.0);
+        ime_text(&mut input, "a");
+        ime_text(&mut input, "a");
+
+        let mut expected = vec![egui::Event::Text("a".to_string())];
+        expected.extend(ime_commit_events("a"));
+        expected.extend(ime_commit_events("a"));
+        assert_eq!(typed(&mut input), expected);
+    }
+}
@@ -0,0 +1,524 @@
+use tauri_runtime_wry::tao::keyboard::{Key, KeyCode};
+
+/// Translates a tao logical key (the key as the current keyboard layout sees it)
+pub(crate) fn translate_logical_key(key: &Key) -> Option<egui::Key> {
+    match key {
+        // handles letters, digits and punctuation in any case, e.g. "a", "A", "/", "+"
+        Key::Character(ch) => egui::Key::from_name(ch),
+        Key::ArrowDown => Some(egui::Key::ArrowDown),
+        Key::ArrowLeft => Some(egui::Key::ArrowLeft),
+        Key::ArrowRight => Some(egui::Key::ArrowRight),
+        Key::ArrowUp => Some(egui::Key::ArrowUp),
+        Key::Escape => Some(egui::Key::Escape),
+        Key::Tab => Some(egui::Key::Tab),
+        Key::Backspace => Some(egui::Key::Backspace),
+        Key::Enter => Some(egui::Key::Enter),
+        Key::Insert => Some(egui::Key::Insert),
+        Key::Delete => Some(egui::Key::Delete),
+        Key::Home => Some(egui::Key::Home),
+        Key::End => Some(egui::Key::End),
+        Key::PageUp => Some(egui::Key::PageUp),
+        Key::PageDown => Some(egui::Key::PageDown),
+        Key::Copy => Some(egui::Key::Copy),
+        Key::Cut => Some(egui::Key::Cut),
+        Key::Paste => Some(egui::Key::Paste),
+        Key::Space => Some(egui::Key::Space),
+        Key::F1 => Some(egui::Key::F1),
+        Key::F2 => Some(egui::Key::F2),
+        Key::F3 => Some(egui::Key::F3),
+        Key::F4 => Some(egui::Key::F4),
+        Key::F5 => Some(egui::Key::F5),
+        Key::F6 => Some(egui::Key::F6),
+        Key::F7 => Some(egui::Key::F7),
+        Key::F8 => Some(egui::Key::F8),
+        Key::F9 => Some(egui::Key::F9),
+        Key::F10 => Some(egui::Key::F10),
+        Key::F11 => Some(egui::Key::F11),
+        Key::F12 => Some(egui::Key::F12),
+        Key::F13 => Some(egui::Key::F13),
+        Key::F14 => Some(egui::Key::F14),
+        Key::F15 => Some(egui::Key::F15),
+        Key::F16 => Some(egui::Key::F16),
+        Key::F17 => Some(egui::Key::F17),
+        Key::F18 => Some(egui::Key::F18),
+        Key::F19 => Some(egui::Key::F19),
+        Key::F20 => Some(egui::Key::F20),
+        Key::F21 => Some(egui::Key::F21),
+        Key::F22 => Some(egui::Key::F22),
+        Key::F23 => Some(egui::Key::F23),
+        Key::F24 => Some(egui::Key::F24),
+        Key::F25 => Some(egui::Key::F25),
+        Key::F26 => Some(egui::Key::F26),
+        Key::F27 => Some(egui::Key::F27),
+        Key::F28 => Some(egui::Key::F28),
+        Key::F29 => Some(egui::Key::F29),
+        Key::F30 => Some(egui::Key::F30),
+        Key::F31 => Some(egui::Key::F31),
+        Key::F32 => Some(egui::Key::F32),
+        Key::F33 => Some(egui::Key::F33),
+        Key::F34 => Some(egui::Key::F34),
+        Key::F35 => Some(egui::Key::F35),
+        Key::BrowserBack => Some(egui::Key::BrowserBack),
+        _ => None,
+    }
+}
+
+/// Translates a tao physical key (the key's position, independent of the layout)
+pub(crate) fn translate_physical_key(key: &KeyCode) -> Option<egui::Key> {
+    match key {
+        KeyCode::ArrowDown => Some(egui::Key::ArrowDown),
+        KeyCode::ArrowLeft => Some(egui::Key::ArrowLeft),
+        KeyCode::ArrowRight => Some(egui::Key::ArrowRight),
+        KeyCode::ArrowUp => Some(egui::Key::ArrowUp),
+        KeyCode::Escape => Some(egui::Key::Escape),
+        KeyCode::Tab => Some(egui::Key::Tab),
+        KeyCode::Backspace => Some(egui::Key::Backspace),
+        KeyCode::Delete => Some(egui::Key::Delete),
+        KeyCode::Enter | KeyCode::NumpadEnter => Some(egui::Key::Enter),
+        KeyCode::Space => Some(egui::Key::Space),
+        KeyCode::Insert => Some(egui::Key::Insert),
+        KeyCode::Home => Some(egui::Key::Home),
+        KeyCode::End => Some(egui::Key::End),
+        KeyCode::PageUp => Some(egui::Key::PageUp),
+        KeyCode::PageDown => Some(egui::Key::PageDown),
+        KeyCode::Copy => Some(egui::Key::Copy),
+        KeyCode::Cut => Some(egui::Key::Cut),
+        KeyCode::Paste => Some(egui::Key::Paste),
+        // there is no physical colon, pipe, braces, plus etc. on a US layout,
+        // those are only reachable through the logical key
+        KeyCode::Comma | KeyCode::NumpadComma => Some(egui::Key::Comma),
+        KeyCode::Period | KeyCode::NumpadDecimal => Some(egui::Key::Period),
+        KeyCode::Minus | KeyCode::NumpadSubtract => Some(egui::Key::Minus),
+        KeyCode::NumpadAdd => Some(egui::Key::Plus),
+        KeyCode::Equal | KeyCode::NumpadEqual => Some(egui::Key::Equals),
+        KeyCode::Semicolon => Some(egui::Key::Semicolon),
+        KeyCode::BracketLeft => Some(egui::Key::OpenBracket),
+        KeyCode::BracketRight => Some(egui::Key::CloseBracket),
+        KeyCode::Backquote => Some(egui::Key::Backtick),
+        KeyCode::Backslash | KeyCode::IntlBackslash => Some(egui::Key::Backslash),
+        KeyCode::Slash | KeyCode::NumpadDivide => Some(egui::Key::Slash),
+        KeyCode::Quote => Some(egui::Key::Quote),
+        KeyCode::Digit0 | KeyCode::Numpad0 => Some(egui::Key::Num0),
+        KeyCode::Digit1 | KeyCode::Numpad1 => Some(egui::Key::Num1),
+        KeyCode::Digit2 | KeyCode::Numpad2 => Some(egui::Key::Num2),
+        KeyCode::Digit3 | KeyCode::Numpad3 => Some(egui::Key::Num3),
+        KeyCode::Digit4 | KeyCode::Numpad4 => Some(egui::Key::Num4),
+        KeyCode::Digit5 | KeyCode::Numpad5 => Some(egui::Key::Num5),
+        KeyCode::Digit6 | KeyCode::Numpad6 => Some(egui::Key::Num6),
+        KeyCode::Digit7 | KeyCode::Numpad7 => Some(egui::Key::Num7),
+        KeyCode::Digit8 | KeyCode::Numpad8 => Some(egui::Key::Num8),
+        KeyCode::Digit9 | KeyCode::Numpad9 => Some(egui::Key::Num9),
+        KeyCode::KeyA => Some(egui::Key::A),
+        KeyCode::KeyB => Some(egui::Key::B),
+        KeyCode::KeyC => Some(egui::Key::C),
+        KeyCode::KeyD => Some(egui::Key::D),
+        KeyCode::KeyE => Some(egui::Key::E),
+        KeyCode::KeyF => Some(egui::Key::F),
+        KeyCode::KeyG => Some(egui::Key::G),
+        KeyCode::KeyH => Some(egui::Key::H),
+        KeyCode::KeyI => Some(egui::Key::I),
+        KeyCode::KeyJ => Some(egui::Key::J),
+        KeyCode::KeyK => Some(egui::Key::K),
+        KeyCode::KeyL => Some(egui::Key::L),
+        KeyCode::KeyM => Some(egui::Key::M),
+        KeyCode::KeyN => Some(egui::Key::N),
+        KeyCode::KeyO => Some(egui::Key::O),
+        KeyCode::KeyP => Some(egui::Key::P),
+        KeyCode::KeyQ => Some(egui::Key::Q),
+        KeyCode::KeyR => Some(egui::Key::R),
+        KeyCode::KeyS => Some(egui::Key::S),
+        KeyCode::KeyT => Some(egui::Key::T),
+        KeyCode::KeyU => Some(egui::Key::U),
+        KeyCode::KeyV => Some(egui::Key::V),
+        KeyCode::KeyW => Some(egui::Key::W),
+        KeyCode::KeyX => Some(egui::Key::X),
+        KeyCode::KeyY => Some(egui::Key::Y),
+        KeyCode::KeyZ => Some(egui::Key::Z),
+        KeyCode::F1 => Some(egui::Key::F1),
+        KeyCode::F2 => Some(egui::Key::F2),
+        KeyCode::F3 => Some(egui::Key::F3),
+        KeyCode::F4 => Some(egui::Key::F4),
+        KeyCode::F5 => Some(egui::Key::F5),
+        KeyCode::F6 => Some(egui::Key::F6),
+        KeyCode::F7 => Some(egui::Key::F7),
+        KeyCode::F8 => Some(egui::Key::F8),
+        KeyCode::F9 => Some(egui::Key::F9),
+        KeyCode::F10 => Some(egui::Key::F10),
+        KeyCode::F11 => Some(egui::Key::F11),
+        KeyCode::F12 => Some(egui::Key::F12),
+        KeyCode::F13 => Some(egui::Key::F13),
+        KeyCode::F14 => Some(egui::Key::F14),
+        KeyCode::F15 => Some(egui::Key::F15),
+        KeyCode::F16 => Some(egui::Key::F16),
+        KeyCode::F17 => Some(egui::Key::F17),
+        KeyCode::F18 => Some(egui::Key::F18),
+        KeyCode::F19 => Some(egui::Key::F19),
+        KeyCode::F20 => Some(egui::Key::F20),
+        KeyCode::F21 => Some(egui::Key::F21),
+        KeyCode::F22 => Some(egui::Key::F22),
+        KeyCode::F23 => Some(egui::Key::F23),
+        KeyCode::F24 => Some(egui::Key::F24),
+        KeyCode::F25 => Some(egui::Key::F25),
+        KeyCode::F26 => Some(egui::Key::F26),
+        KeyCode::F27 => Some(egui::Key::F27),
+        KeyCode::F28 => Some(egui::Key::F28),
+        KeyCode::F29 => Some(egui::Key::F29),
+        KeyCode::F30 => Some(egui::Key::F30),
+        KeyCode::F31 => Some(egui::Key::F31),
+        KeyCode::F32 => Some(egui::Key::F32),
+        KeyCode::F33 => Some(egui::Key::F33),
+        KeyCode::F34 => Some(egui::Key::F34),
+        KeyCode::F35 => Some(egui::Key::F35),
+        KeyCode::BrowserBack => Some(egui::Key::BrowserBack),
+        _ => None,
+    }
+}
+
+/// Whether the key press should cut the selection, e.g. Cmd+X on macOS and Ctrl+X elsewhere
+pub(crate) fn is_cut_command(modifiers: egui::Modifiers, key: egui::Key) -> bool {
+    key == egui::Key::Cut
+        || (modifiers.command && key == egui::Key::X)
+        || (cfg!(target_os = "windows") && modifiers.shift && key == egui::Key::Delete)
+}
+
+/// Whether the key press should copy the selection, e.g. Cmd+C on macOS and Ctrl+C elsewhere
+pub(crate) fn is_copy_command(modifiers: egui::Modifiers, key: egui::Key) -> bool {
+    key == egui::Key::Copy
+        || (modifiers.command && key == egui::Key::C)
+        || (cfg!(target_os = "windows") && modifiers.ctrl && key == egui::Key::Insert)
+}
+
+/// Whether the key press should paste the clipboard, e.g. Cmd+V on macOS and Ctrl+V elsewhere
+pub(crate) fn is_paste_command(modifiers: egui::Modifiers, key: egui::Key) -> bool {
+    key == egui::Key::Paste
+        || (modifiers.command && key == egui::Key::V)
+        || (cfg!(target_os = "windows") && modifiers.shift && key == egui::Key::Insert)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Every tao logical key we expect to reach egui, with the egui key it maps to
+    fn logical_table() -> Vec<(Key<'static>, egui::Key)> {
+        vec![
+            (Key::ArrowDown, egui::Key::ArrowDown),
+            (Key::ArrowLeft, egui::Key::ArrowLeft),
+            (Key::ArrowRight, egui::Key::ArrowRight),
+            (Key::ArrowUp, egui::Key::ArrowUp),
+            (Key::Escape, egui::Key::Escape),
+            (Key::Tab, egui::Key::Tab),
+            (Key::Backspace, egui::Key::Backspace),
+            (Key::Enter, egui::Key::Enter),
+            (Key::Insert, egui::Key::Insert),
+            (Key::Delete, egui::Key::Delete),
+            (Key::Home, egui::Key::Home),
+            (Key::End, egui::Key::End),
+            (Key::PageUp, egui::Key::PageUp),
+            (Key::PageDown, egui::Key::PageDown),
+            (Key::Copy, egui::Key::Copy),
+            (Key::Cut, egui::Key::Cut),
+            (Key::Paste, egui::Key::Paste),
+            (Key::Space, egui::Key::Space),
+            (Key::F1, egui::Key::F1),
+            (Key::F2, egui::Key::F2),
+            (Key::F3, egui::Key::F3),
+            (Key::F4, egui::Key::F4),
+            (Key::F5, egui::Key::F5),
+            (Key::F6, egui::Key::F6),
+            (Key::F7, egui::Key::F7),
+            (Key::F8, egui::Key::F8),
+            (Key::F9, egui::Key::F9),
+            (Key::F10, egui::Key::F10),
+            (Key::F11, egui::Key::F11),
+            (Key::F12, egui::Key::F12),
+            (Key::F13, egui::Key::F13),
+            (Key::F14, egui::Key::F14),
+            (Key::F15, egui::Key::F15),
+            (Key::F16, egui::Key::F16),
+            (Key::F17, egui::Key::F17),
+            (Key::F18, egui::Key::F18),
+            (Key::F19, egui::Key::F19),
+            (Key::F20, egui::Key::F20),
+            (Key::F21, egui::Key::F21),
+            (Key::F22, egui::Key::F22),
+            (Key::F23, egui::Key::F23),
+            (Key::F24, egui::Key::F24),
+            (Key::F25, egui::Key::F25),
+            (Key::F26, egui::Key::F26),
+            (Key::F27, egui::Key::F27),
+            (Key::F28, egui::Key::F28),
+            (Key::F29, egui::Key::F29),
+            (Key::F30, egui::Key::F30),
+            (Key::F31, egui::Key::F31),
+            (Key::F32, egui::Key::F32),
+            (Key::F33, egui::Key::F33),
+            (Key::F34, egui::Key::F34),
+            (Key::F35, egui::Key::F35),
+            (Key::BrowserBack, egui::Key::BrowserBack),
+            (Key::Character(" "), egui::Key::Space),
+            (Key::Character(":"), egui::Key::Colon),
+            (Key::Character(","), egui::Key::Comma),
+            (Key::Character("-"), egui::Key::Minus),
+            (Key::Character("."), egui::Key::Period),
+            (Key::Character("+"), egui::Key::Plus),
+            (Key::Character("="), egui::Key::Equals),
+            (Key::Character(";"), egui::Key::Semicolon),
+            (Key::Character("["), egui::Key::OpenBracket),
+            (Key::Character("]"), egui::Key::CloseBracket),
+            (Key::Character("{"), egui::Key::OpenCurlyBracket),
+            (Key::Character("}"), egui::Key::CloseCurlyBracket),
+            (Key::Character("`"), egui::Key::Backtick),
+            (Key::Character("\\"), egui::Key::Backslash),
+            (Key::Character("/"), egui::Key::Slash),
+            (Key::Character("|"), egui::Key::Pipe),
+            (Key::Character("?"), egui::Key::Questionmark),
+            (Key::Character("!"), egui::Key::Exclamationmark),
+            (Key::Character("'"), egui::Key::Quote),
+            (Key::Character("0"), egui::Key::Num0),
+            (Key::Character("1"), egui::Key::Num1),
+            (Key::Character("2"), egui::Key::Num2),
+            (Key::Character("3"), egui::Key::Num3),
+            (Key::Character("4"), egui::Key::Num4),
+            (Key::Character("5"), egui::Key::Num5),
+            (Key::Character("6"), egui::Key::Num6),
+            (Key::Character("7"), egui::Key::Num7),
+            (Key::Character("8"), egui::Key::Num8),
+            (Key::Character("9"), egui::Key::Num9),
+            (Key::Character("a"), egui::Key::A),
+            (Key::Character("A"), egui::Key::A),
+            (Key::Character("b"), egui::Key::B),
+            (Key::Character("B"), egui::Key::B),
+            (Key::Character("c"), egui::Key::C),
+            (Key::Character("C"), egui::Key::C),
+            (Key::Character("d"), egui::Key::D),
+            (Key::Character("D"), egui::Key::D),
+            (Key::Character("e"), egui::Key::E),
+            (Key::Character("E"), egui::Key::E),
+            (Key::Character("f"), egui::Key::F),
+            (Key::Character("F"), egui::Key::F),
+            (Key::Character("g"), egui::Key::G),
+            (Key::Character("G"), egui::Key::G),
+            (Key::Character("h"), egui::Key::H),
+            (Key::Character("H"), egui::Key::H),
+            (Key::Character("i"), egui::Key::I),
+            (Key::Character("I"), egui::Key::I),
+            (Key::Character("j"), egui::Key::J),
+            (Key::Character("J"), egui::Key::J),
+            (Key::Character("k"), egui::Key::K),
+            (Key::Character("K"), egui::Key::K),
+            (Key::Character("l"), egui::Key::L),
+            (Key::Character("L"), egui::Key::L),
+            (Key::Character("m"), egui::Key::M),
+            (Key::Character("M"), egui::Key::M),
+            (Key::Character("n"), egui::Key::N),
+            (Key::Character("N"), egui::Key::N),
+            (Key::Character("o"), egui::Key::O),
+            (Key::Character("O"), egui::Key::O),
+            (Key::Character("p"), egui::Key::P),
+            (Key::Character("P"), egui::Key::P),
+            (Key::Character("q"), egui::Key::Q),
+            (Key::Character("Q"), egui::Key::Q),
+            (Key::Character("r"), egui::Key::R),
+            (Key::Character("R"), egui::Key::R),
+            (Key::Character("s"), egui::Key::S),
+            (Key::Character("S"), egui::Key::S),
+            (Key::Character("t"), egui::Key::T),
+            (Key::Character("T"), egui::Key::T),
+            (Key::Character("u"), egui::Key::U),
+            (Key::Character("U"), egui::Key::U),
+            (Key::Character("v"), egui::Key::V),
+            (Key::Character("V"), egui::Key::V),
+            (Key::Character("w"), egui::Key::W),
+            (Key::Character("W"), egui::Key::W),
+            (Key::Character("x"), egui::Key::X),
+            (Key::Character("X"), egui::Key::X),
+            (Key::Character("y"), egui::Key::Y),
+            (Key::Character("Y"), egui::Key::Y),
+            (Key::Character("z"), egui::Key::Z),
+            (Key::Character("Z"), egui::Key::Z),
+        ]
+    }
+
+    /// Every tao physical key we expect to reach egui, with the egui key it maps to
+    fn physical_table() -> Vec<(KeyCode, egui::Key)> {
+        vec![
+            (KeyCode::ArrowDown, egui::Key::ArrowDown),
+            (KeyCode::ArrowLeft, egui::Key::ArrowLeft),
+            (KeyCode::ArrowRight, egui::Key::ArrowRight),
+            (KeyCode::ArrowUp, egui::Key::ArrowUp),
+            (KeyCode::Escape, egui::Key::Escape),
+            (KeyCode::Tab, egui::Key::Tab),
+            (KeyCode::Backspace, egui::Key::Backspace),
+            (KeyCode::Delete, egui::Key::Delete),
+            (KeyCode::Enter, egui::Key::Enter),
+            (KeyCode::NumpadEnter, egui::Key::Enter),
+            (KeyCode::Space, egui::Key::Space),
+            (KeyCode::Insert, egui::Key::Insert),
+            (KeyCode::Home, egui::Key::Home),
+            (KeyCode::End, egui::Key::End),
+            (KeyCode::PageUp, egui::Key::PageUp),
+            (KeyCode::PageDown, egui::Key::PageDown),
+            (KeyCode::Copy, egui::Key::Copy),
+            (KeyCode::Cut, egui::Key::Cut),
+            (KeyCode::Paste, egui::Key::Paste),
+            (KeyCode::Comma, egui::Key::Comma),
+            (KeyCode::NumpadComma, egui::Key::Comma),
+            (KeyCode::Period, egui::Key::Period),
+            (KeyCode::NumpadDecimal, egui::Key::Period),
+            (KeyCode::Minus, egui::Key::Minus),
+            (KeyCode::NumpadSubtract, egui::Key::Minus),
+            (KeyCode::NumpadAdd, egui::Key::Plus),
+            (KeyCode::Equal, egui::Key::Equals),
+            (KeyCode::NumpadEqual, egui::Key::Equals),
+            (KeyCode::Semicolon, egui::Key::Semicolon),
+            (KeyCode::BracketLeft, egui::Key::OpenBracket),
+            (KeyCode::BracketRight, egui::Key::CloseBracket),
+            (KeyCode::Backquote, egui::Key::Backtick),
+            (KeyCode::Backslash, egui::Key::Backslash),
+            (KeyCode::IntlBackslash, egui::Key::Backslash),
+            (KeyCode::Slash, egui::Key::Slash),
+            (KeyCode::NumpadDivide, egui::Key::Slash),
+            (KeyCode::Quote, egui::Key::Quote),
+            (KeyCode::Digit0, egui::Key::Num0),
+            (KeyCode::Numpad0, egui::Key::Num0),
+            (KeyCode::Digit1, egui::Key::Num1),
+            (KeyCode::Numpad1, egui::Key::Num1),
+            (KeyCode::Digit2, egui::Key::Num2),
+            (KeyCode::Numpad2, egui::Key::Num2),
+            (KeyCode::Digit3, egui::Key::Num3),
+            (KeyCode::Numpad3, egui::Key::Num3),
+            (KeyCode::Digit4, egui::Key::Num4),
+            (KeyCode::Numpad4, egui::Key::Num4),
+            (KeyCode::Digit5, egui::Key::Num5),
+            (KeyCode::Numpad5, egui::Key::Num5),
+            (KeyCode::Digit6, egui::Key::Num6),
+            (KeyCode::Numpad6, egui::Key::Num6),
+            (KeyCode::Digit7, egui::Key::Num7),
+            (KeyCode::Numpad7, egui::Key::Num7),
+            (KeyCode::Digit8, egui::Key::Num8),
+            (KeyCode::Numpad8, egui::Key::Num8),
+            (KeyCode::Digit9, egui::Key::Num9),
+            (KeyCode::Numpad9, egui::Key::Num9),
+            (KeyCode::KeyA, egui::Key::A),
+            (KeyCode::KeyB, egui::Key::B),
+            (KeyCode::KeyC, egui::Key::C),
+            (KeyCode::KeyD, egui::Key::D),
+            (KeyCode::KeyE, egui::Key::E),
+            (KeyCode::KeyF, egui::Key::F),
+            (KeyCode::KeyG, egui::Key::G),
+            (KeyCode::KeyH, egui::Key::H),
+            (KeyCode::KeyI, egui::Key::I),
+            (KeyCode::KeyJ, egui::Key::J),
+            (KeyCode::KeyK, egui::Key::K),
+            (KeyCode::KeyL, egui::Key::L),
+            (KeyCode::KeyM, egui::Key::M),
+            (KeyCode::KeyN, egui::Key::N),
+            (KeyCode::KeyO, egui::Key::O),
+            (KeyCode::KeyP, egui::Key::P),
+            (KeyCode::KeyQ, egui::Key::Q),
+            (KeyCode::KeyR, egui::Key::R),
+            (KeyCode::KeyS, egui::Key::S),
+            (KeyCode::KeyT, egui::Key::T),
+            (KeyCode::KeyU, egui::Key::U),
+            (KeyCode::KeyV, egui::Key::V),
+            (KeyCode::KeyW, egui::Key::W),
+            (KeyCode::KeyX, egui::Key::X),
+            (KeyCode::KeyY, egui::Key::Y),
+            (KeyCode::KeyZ, egui::Key::Z),
+            (KeyCode::F1, egui::Key::F1),
+            (KeyCode::F2, egui::Key::F2),
+            (KeyCode::F3, egui::Key::F3),
+            (KeyCode::F4, egui::Key::F4),
+            (KeyCode::F5, egui::Key::F5),
+            (KeyCode::F6, egui::Key::F6),
+            (KeyCode::F7, egui::Key::F7),
+            (KeyCode::F8, egui::Key::F8),
+            (KeyCode::F9, egui::Key::F9),
+            (KeyCode::F10, egui::Key::F10),
+            (KeyCode::F11, egui::Key::F11),
+            (KeyCode::F12, egui::Key::F12),
+            (KeyCode::F13, egui::Key::F13),
+            (KeyCode::F14, egui::Key::F14),
+            (KeyCode::F15, egui::Key::F15),
+            (KeyCode::F16, egui::Key::F16),
+            (KeyCode::F17, egui::Key::F17),
+            (KeyCode::F18, egui::Key::F18),
+            (KeyCode::F19, egui::Key::F19),
+            (KeyCode::F20, egui::Key::F20),
+            (KeyCode::F21, egui::Key::F21),
+            (KeyCode::F22, egui::Key::F22),
+            (KeyCode::F23, egui::Key::F23),
+            (KeyCode::F24, egui::Key::F24),
+            (KeyCode::F25, egui::Key::F25),
+            (KeyCode::F26, egui::Key::F26),
+            (KeyCode::F27, egui::Key::F27),
+            (KeyCode::F28, egui::Key::F28),
+            (KeyCode::F29, egui::Key::F29),
+            (KeyCode::F30, egui::Key::F30),
+            (KeyCode::F31, egui::Key::F31),
+            (KeyCode::F32, egui::Key::F32),
+            (KeyCode::F33, egui::Key::F33),
+            (KeyCode::F34, egui::Key::F34),
+            (KeyCode::F35, egui::Key::F35),
+            (KeyCode::BrowserBack, egui::Key::BrowserBack),
+        ]
+    }
+
+    #[test]
+    fn logical_keys_translate() {
+        for (key, expected) in logical_table() {
+            assert_eq!(translate_logical_key(&key), Some(expected), "{key:?}");
+        }
+    }
+
+    #[test]
+    fn physical_keys_translate() {
+        for (code, expected) in physical_table() {
+            assert_eq!(translate_physical_key(&code), Some(expected), "{code:?}");
+        }
+    }
+
+    #[test]
+    fn every_egui_key_is_reachable() {
+        let logical = logical_table();
+        for key in egui::Key::ALL {
+            assert!(
+                logical.iter().any(|(_, k)| k == key),
+                "{key:?} has no logical tao key"
+            );
+        }
+    }
+
+    #[test]
+    fn clipboard_shortcuts_need_command() {
+        let none = egui::Modifiers::NONE;
+        let command = egui::Modifiers::COMMAND;
+
+        assert!(is_copy_command(command, egui::Key::C));
+        assert!(is_cut_command(command, egui::Key::X));
+        assert!(is_paste_command(command, egui::Key::V));
+
+        assert!(!is_copy_command(none, egui::Key::C));
+        assert!(!is_cut_command(none, egui::Key::X));
+        assert!(!is_paste_command(none, egui::Key::V));
+        assert!(!is_copy_command(command, egui::Key::V));
+    }
+
+    #[test]
+    fn dedicated_clipboard_keys_need_no_modifiers() {
+        let none = egui::Modifiers::NONE;
+
+        assert!(is_copy_command(none, egui::Key::Copy));
+        assert!(is_cut_command(none, egui::Key::Cut));
+        assert!(is_paste_command(none, egui::Key::Paste));
+    }
+
+    #[test]
+    fn unmapped_keys_translate_to_none() {
+        assert_eq!(translate_logical_key(&Key::Shift), None);
+        assert_eq!(translate_logical_key(&Key::Character("é")), None);
+        assert_eq!(translate_physical_key(&KeyCode::ShiftLeft), None);
+        assert_eq!(translate_physical_key(&KeyCode::NumpadMultiply), None);
+    }
+}
@@ -1,8 +1,36 @@
+mod app;
+mod clipboard;
+mod handle;
+mod ime;
+mod input;
+mod keyboard;
+mod native_textures;
+mod offscreen;
+mod open_url;
+mod overlay;
 mod plugin;
+mod region;
+mod render_error;
 mod renderer;
+mod repaint;
 mod utils;
+mod viewport;
 
-pub use plugin::{AppHandleExt, Builder};
+pub use app::{EguiApp, Frame, Storage, WindowCommand};
+pub use clipboard::{Clipboard, MemoryClipboard, SystemClipboard};
+pub use handle::EguiHandle;
+pub use offscreen::render_to_image;
+pub use open_url::{
+    open_url_in_browser, OpenUrlHandler, UrlBlockedPayload, UrlSchemePolicy, URL_BLOCKED_EVENT,
+};
+pub use overlay::OverlayLayer;
+pub use plugin::{AppHandleExt, Builder, EguiWindowState};
+pub use region::{EguiRegion, LogicalRect, PhysicalRect};
+pub use render_error::{
+    RenderErrorHandler, RenderErrorKind, RenderErrorPayload, RENDER_ERROR_EVENT,
+};
+pub use renderer::{EguiRendererOptions, GpuContext, TargetFormat};
 
 // re-export for convenience
 pub use egui;
+pub use egui_wgpu::wgpu;
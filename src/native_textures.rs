@@ -0,0 +1,219 @@
+use std::sync::{Arc, Mutex, RwLock, Weak};
+
+use anyhow::Error;
+use egui_wgpu::wgpu;
+
+/// The native textures of an egui window, registered under the same ids
+/// in the renderer of every window it draws into, child viewports included.
+///
+/// egui-wgpu numbers native textures one after another per renderer, so a renderer
+/// that joins later is replayed the whole history, freed textures included.
+#[derive(Clone)]
+pub(crate) struct NativeTextures {
+    inner: Arc<Mutex<Registry>>,
+}
+
+struct Registry {
+    device: wgpu::Device,
+    /// Indexed by `TextureId::User`, `None` once freed
+    textures: Vec<Option<(wgpu::TextureView, wgpu::FilterMode)>>,
+    renderers: Vec<Weak<RwLock<egui_wgpu::Renderer>>>,
+    /// Stands in for freed textures while replaying
+    placeholder: Option<wgpu::TextureView>,
+}
+
+impl NativeTextures {
+    pub fn new(device: wgpu::Device) -> Self {
+        Self {
+            inner: Arc::new(Mutex::new(Registry {
+                device,
+                textures: Vec::new(),
+                renderers: Vec::new(),
+                placeholder: None,
+            })),
+        }
+    }
+
+    /// Registers the textures so far in a new renderer, and the ones to come.
+    pub fn add_renderer(&self, renderer: &Arc<RwLock<egui_wgpu::Renderer>>) {
+        let mut registry = self.inner.lock().unwrap();
+        let placeholder = registry
+            .textures
+            .iter()
+            .any(Option::is_none)
+            .then(|| registry.placeholder());
+
+        {
+            let mut egui_renderer = renderer.write().unwrap();
+            for (i, texture) in registry.textures.iter().enumerate() {
+                let (view, filter) = match texture {
+                    Some((view, filter)) => (view, *filter),
+                    None => (placeholder.as_ref().unwrap(), wgpu::FilterMode::Nearest),
+                };
+                let id = egui_renderer.register_native_texture(&registry.device, view, filter);
+                debug_assert_eq!(id, egui::TextureId::User(i as u64));
+                if texture.is_none() {
+                    egui_renderer.free_texture(&id);
+                }
+            }
+        }
+
+        registry.renderers.push(Arc::downgrade(renderer));
+    }
+
+    pub fn register(
+        &self,
+        texture: &wgpu::TextureView,
+        filter: wgpu::FilterMode,
+    ) -> egui::TextureId {
+        let mut registry = self.inner.lock().unwrap();
+        let id = egui::TextureId::User(registry.textures.len() as u64);
+        registry.textures.push(Some((texture.clone(), filter)));
+        registry.for_each_renderer(|egui_renderer, device| {
+            egui_renderer.register_native_texture(device, texture, filter);
+        });
+        id
+    }
+
+    pub fn update(
+        &self,
+        id: egui::TextureId,
+        texture: &wgpu::TextureView,
+        filter: wgpu::FilterMode,
+    ) -> Result<(), Error> {
+        let mut registry = self.inner.lock().unwrap();
+        let Some(entry) = registry.entry(id) else {
+            return Err(Error::msg(format!(
+                "{:?} isn't a registered native texture",
+                id
+            )));
+        };
+        *entry = Some((texture.clone(), filter));
+        registry.for_each_renderer(|egui_renderer, device| {
+            egui_renderer.update_egui_texture_from_wgpu_texture(device, texture, filter, id);
+        });
+        Ok(())
+    }
+
+    pub fn free(&self, id: egui::TextureId) {
+        let mut registry = self.inner.lock().unwrap();
+        if let Some(entry) = registry.entry(id) {
+            *entry = None;
+            registry.for_each_renderer(|egui_renderer, _| egui_renderer.free_texture(&id));
+        }
+    }
+}
+
+impl Registry {
+    /// The entry of a registered texture that wasn't freed.
+    fn entry(
+        &mut self,
+        id: egui::TextureId,
+    ) -> Option<&mut Option<(wgpu::TextureView, wgpu::FilterMode)>> {
+        let egui::TextureId::User(index) = id else {
+            return None;
+        };
+        self.textures
+            .get_mut(index as usize)
+            .filter(|entry| entry.is_some())
+    }
+
+    /// Runs `f` on the renderers that are still around, forgetting the others.
+    fn for_each_renderer(&mut self, mut f: impl FnMut(&mut egui_wgpu::Renderer, &wgpu::Device)) {
+        let device = &self.device;
+        self.renderers.retain(|renderer| match renderer.upgrade() {
+            Some(renderer) => {
+                f(&mut renderer.write().unwrap(), device);
+                true
+            }
+            None => false,
+        });
+    }
+
+    fn placeholder(&mut self) -> wgpu::TextureView {
+        let device = &self.device;
+        self.placeholder
+            .get_or_insert_with(|| {
+                device
+                    .create_texture(&wgpu::TextureDescriptor {
+                        label: Some("freed native texture"),
+                        size: wgpu::Extent3d {
+                            width: 1,
+                            height: 1,
+                            depth_or_array_layers: 1,
+                        },
+                        mip_level_count: 1,
+                        sample_count: 1,
+                        dimension: wgpu::TextureDimension::D2,
+                        format: wgpu::TextureFormat::Rgba8Unorm,
+                        usage: wgpu::TextureUsages::TEXTURE_BINDING,
+                        view_formats: &[],
+                    })
+                    .create_view(&wgpu::TextureViewDescriptor::default())
+            })
+            .clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::renderer::{EguiRendererOptions, GpuContext, Renderer};
+
+    fn texture_view(device: &wgpu::Device) -> wgpu::TextureView {
+        device
+            .create_texture(&wgpu::TextureDescriptor {
+                label: None,
+                size: wgpu::Extent3d {
+                    width: 4,
+                    height: 4,
+                    depth_or_array_layers: 1,
+                },
+                mip_level_count: 1,
+                sample_count: 1,
+                dimension: wgpu::TextureDimension::D2,
+                format: wgpu::TextureFormat::Rgba8Unorm,
+                usage: wgpu::TextureUsages::TEXTURE_BINDING,
+                view_formats: &[],
+            })
+            .create_view(&wgpu::TextureViewDescriptor::default())
+    }
+
+    #[test]
+    fn textures_share_their_id_across_renderers() {
+        let options = EguiRendererOptions::default();
+        let Ok(gpu) = tauri::async_runtime::block_on(GpuContext::new_headless(&options)) else {
+            eprintln!("No wgpu adapter, skipping");
+            return;
+        };
+        let new_renderer = || Renderer::new_offscreen(&gpu, 4, 4, &options).egui_renderer();
+        let filter = wgpu::FilterMode::Linear;
+
+        let textures = NativeTextures::new(gpu.device.clone());
+        let root = new_renderer();
+        textures.add_renderer(&root);
+
+        let freed = textures.register(&texture_view(&gpu.device), filter);
+        let kept = textures.register(&texture_view(&gpu.device), filter);
+        textures.free(freed);
+
+        // A viewport opened later knows the texture under the same id
+        let viewport = new_renderer();
+        textures.add_renderer(&viewport);
+        let added = textures.register(&texture_view(&gpu.device), filter);
+
+        for renderer in [&root, &viewport] {
+            let renderer = renderer.read().unwrap();
+            assert!(renderer.texture(&freed).is_none());
+            assert!(renderer.texture(&kept).is_some());
+            assert!(renderer.texture(&added).is_some());
+        }
+
+        assert!(textures
+            .update(freed, &texture_view(&gpu.device), filter)
+            .is_err());
+        assert!(textures
+            .update(kept, &texture_view(&gpu.device), filter)
+            .is_ok());
+    }
+}
@@ -0,0 +1,261 @@
+use anyhow::Error;
+
+use crate::renderer::{EguiRendererOptions, GpuContext, Renderer};
+
+/// Renders a single egui frame without any window, e.g. for snapshot tests or thumbnails.
+///
+/// `size` is in points, the image is `size * pixels_per_point` pixels large.
+/// Renders with `gpu_context`, e.g. [`crate::AppHandleExt::egui_gpu_context`] in a running app
+/// or [`GpuContext::new_headless`], which falls back to a software adapter when there's no GPU.
+///
+/// ```no_run
+/// use tauri_plugin_egui::{EguiRendererOptions, GpuContext};
+///
+/// let options = EguiRendererOptions::default();
+/// let gpu_context = tauri::async_runtime::block_on(GpuContext::new_headless(&options)).unwrap();
+/// let image = tauri_plugin_egui::render_to_image(
+///     &gpu_context,
+///     |ctx| {
+///         egui::CentralPanel::default().show(ctx, |ui| ui.label("Hello"));
+///     },
+///     egui::vec2(200.0, 100.0),
+///     2.0,
+/// )
+/// .unwrap();
+/// assert_eq!(image.size, [400, 200]);
+/// ```
+pub fn render_to_image(
+    gpu_context: &GpuContext,
+    mut ui_fn: impl FnMut(&egui::Context),
+    size: egui::Vec2,
+    pixels_per_point: f32,
+) -> Result<egui::ColorImage, Error> {
+    let width = (size.x * pixels_per_point).round() as u32;
+    let height = (size.y * pixels_per_point).round() as u32;
+    if width == 0 || height == 0 {
+        return Err(Error::msg("Can't render an egui frame without any pixels."));
+    }
+
+    let options = EguiRendererOptions::default();
+    let mut renderer = Renderer::new_offscreen(gpu_context, width, height, &options);
+
+    let context = egui::Context::default();
+    let mut raw_input = egui::RawInput {
+        screen_rect: Some(egui::Rect::from_min_size(egui::Pos2::ZERO, size)),
+        ..Default::default()
+    };
+    raw_input
+        .viewports
+        .entry(egui::ViewportId::ROOT)
+        .or_default()
+        .native_pixels_per_point = Some(pixels_per_point);
+
+    let egui::FullOutput {
+        textures_delta,
+        shapes,
+        pixels_per_point,
+        ..
+    } = context.run(raw_input, |ctx| ui_fn(ctx));
+
+    let paint_jobs = context.tessellate(shapes, pixels_per_point);
+    let screen_descriptor = egui_wgpu::ScreenDescriptor {
+        size_in_pixels: [width, height],
+        pixels_per_point,
+    };
+
+    renderer.capture_frame(
+        screen_descriptor,
+        &paint_jobs,
+        textures_delta,
+        wgpu::Color::TRANSPARENT,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use egui_wgpu::wgpu;
+    use wgpu::util::DeviceExt as _;
+
+    const SHADER: &str = "
+        @vertex
+        fn vs_main(@builtin(vertex_index) index: u32) -> @builtin(position) vec4<f32> {
+            // A triangle covering the whole viewport
+            let uv = vec2<f32>(f32((index << 1u) & 2u), f32(index & 2u));
+            return vec4<f32>(uv * 2.0 - 1.0, 0.0, 1.0);
+        }
+
+        @group(0) @binding(0) var<uniform> color: vec4<f32>;
+
+        @fragment
+        fn fs_main() -> @location(0) vec4<f32> {
+            return color;
+        }
+    ";
+
+    /// Fills its rect with red using a pipeline of its own.
+    struct RedFill;
+
+    struct RedFillResources {
+        pipeline: wgpu::RenderPipeline,
+        color: wgpu::Buffer,
+        bind_group: wgpu::BindGroup,
+    }
+
+    impl egui_wgpu::CallbackTrait for RedFill {
+        fn prepare(
+            &self,
+            device: &wgpu::Device,
+            _queue: &wgpu::Queue,
+            _screen_descriptor: &egui_wgpu::ScreenDescriptor,
+            _egui_encoder: &mut wgpu::CommandEncoder,
+            callback_resources: &mut egui_wgpu::CallbackResources,
+        ) -> Vec<wgpu::CommandBuffer> {
+            if !callback_resources.contains::<RedFillResources>() {
+                let module = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+                    label: Some("red fill"),
+                    source: wgpu::ShaderSource::Wgsl(SHADER.into()),
+                });
+                let pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+                    label: Some("red fill"),
+                    layout: None,
+                    vertex: wgpu::VertexState {
+                        module: &module,
+                        entry_point: Some("vs_main"),
+                        compilation_options: Default::default(),
+                        buffers: &[],
+                    },
+                    fragment: Some(wgpu::FragmentState {
+                        module: &module,
+                        entry_point: Some("fs_main"),
+                        compilation_options: Default::default(),
+                        // Offscreen frames are drawn into Rgba8Unorm textures
+                        targets: &[Some(wgpu::TextureFormat::Rgba8Unorm.into())],
+                    }),
+                    primitive: Default::default(),
+                    depth_stencil: None,
+                    multisample: Default::default(),
+                    multiview: None,
+                    cache: None,
+                });
+                let color = device.create_buffer(&wgpu::BufferDescriptor {
+                    label: Some("red fill color"),
+                    size: 16,
+                    usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+                    mapped_at_creation: false,
+                });
+                let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+                    label: Some("red fill"),
+                    layout: &pipeline.get_bind_group_layout(0),
+                    entries: &[wgpu::BindGroupEntry {
+                        binding: 0,
+                        resource: color.as_entire_binding(),
+                    }],
+                });
+                callback_resources.insert(RedFillResources {
+                    pipeline,
+                    color,
+                    bind_group,
+                });
+            }
+
+            // Upload the color with commands of our own, which have to run before egui's pass
+            let resources: &RedFillResources = callback_resources.get().unwrap();
+            let red = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                label: Some("red"),
+                contents: &[1.0f32, 0.0, 0.0, 1.0].map(f32::to_ne_bytes).concat(),
+                usage: wgpu::BufferUsages::COPY_SRC,
+            });
+            let mut encoder = device.create_command_encoder(&Default::default());
+            encoder.copy_buffer_to_buffer(&red, 0, &resources.color, 0, 16);
+            vec![encoder.finish()]
+        }
+
+        fn paint(
+            &self,
+            _info: egui::PaintCallbackInfo,
+            render_pass: &mut wgpu::RenderPass<'static>,
+            callback_resources: &egui_wgpu::CallbackResources,
+        ) {
+            let resources: &RedFillResources = callback_resources.get().unwrap();
+            render_pass.set_pipeline(&resources.pipeline);
+            render_pass.set_bind_group(0, &resources.bind_group, &[]);
+            render_pass.draw(0..3, 0..1);
+        }
+    }
+
+    #[test]
+    fn renders_panels_and_textures_at_the_given_scale() {
+        let options = EguiRendererOptions::default();
+        let Ok(gpu) = tauri::async_runtime::block_on(GpuContext::new_headless(&options)) else {
+            eprintln!("No wgpu adapter, skipping");
+            return;
+        };
+
+        let panel = egui::Color32::from_rgb(0x20, 0x40, 0x80);
+        let side = egui::Color32::from_rgb(0xc0, 0x60, 0x10);
+        let texel = egui::Color32::from_rgb(0x10, 0xa0, 0x30);
+        let mut texture = None;
+        let image = render_to_image(
+            &gpu,
+            |ctx| {
+                egui::SidePanel::left("side")
+                    .exact_width(10.0)
+                    .resizable(false)
+                    .frame(egui::Frame::NONE.fill(side))
+                    .show(ctx, |_| {});
+                egui::CentralPanel::default()
+                    .frame(egui::Frame::NONE.fill(panel))
+                    .show(ctx, |ui| {
+                        // Uploaded with the frame's texture changes
+                        let texture = texture.get_or_insert_with(|| {
+                            ctx.load_texture(
+                                "texel",
+                                egui::ColorImage::new([2, 2], vec![texel; 4]),
+                                egui::TextureOptions::NEAREST,
+                            )
+                        });
+                        let rect = egui::Rect::from_min_size(
+                            egui::pos2(30.0, 0.0),
+                            egui::vec2(10.0, 10.0),
+                        );
+                        let uv = egui::Rect::from_min_max(egui::Pos2::ZERO, egui::pos2(1.0, 1.0));
+                        ui.painter()
+                            .image(texture.id(), rect, uv, egui::Color32::WHITE);
+                    });
+            },
+            egui::vec2(40.0, 20.0),
+            2.0,
+        )
+        .unwrap();
+
+        assert_eq!(image.size, [80, 40]);
+        assert_eq!(image[(5, 30)], side);
+        assert_eq!(image[(40, 30)], panel);
+        assert_eq!(image[(70, 10)], texel);
+    }
+
+    #[test]
+    fn paint_callbacks_draw_with_their_own_pipeline() {
+        let options = EguiRendererOptions::default();
+        let Ok(gpu) = tauri::async_runtime::block_on(GpuContext::new_headless(&options)) else {
+            eprintln!("No wgpu adapter, skipping");
+            return;
+        };
+
+        let image = render_to_image(
+            &gpu,
+            |ctx| {
+                let rect = egui::Rect::from_min_size(egui::Pos2::ZERO, egui::vec2(20.0, 40.0));
+                ctx.layer_painter(egui::LayerId::background())
+                    .add(egui_wgpu::Callback::new_paint_callback(rect, RedFill));
+            },
+            egui::vec2(40.0, 40.0),
+            1.0,
+        )
+        .unwrap();
+
+        assert_eq!(image[(10, 20)], egui::Color32::RED);
+        assert_eq!(image[(30, 20)], egui::Color32::TRANSPARENT);
+    }
+}
@@ -0,0 +1,132 @@
+use tauri::{AppHandle, Emitter, Url};
+
+/// Emitted when an egui window tries to open a URL that the
+/// [`UrlSchemePolicy`] does not allow, with a [`UrlBlockedPayload`].
+pub const URL_BLOCKED_EVENT: &str = "egui://url-blocked";
+
+/// A hook that opens the URLs requested by egui (e.g. via `ui.hyperlink`).
+pub type OpenUrlHandler = Box<dyn Fn(&AppHandle, &egui::OpenUrl) + Send>;
+
+/// Decides which URL schemes egui windows are allowed to open.
+///
+/// Defaults to allowing only `http`, `https` and `mailto` links.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum UrlSchemePolicy {
+    /// Open every URL, regardless of its scheme. URLs that can't be parsed are still blocked.
+    AllowAll,
+    /// Only open URLs whose scheme is in the list.
+    Allow(Vec<String>),
+    /// Open every URL except the ones whose scheme is in the list.
+    Deny(Vec<String>),
+}
+
+impl Default for UrlSchemePolicy {
+    fn default() -> Self {
+        Self::Allow(vec!["http".into(), "https".into(), "mailto".into()])
+    }
+}
+
+impl UrlSchemePolicy {
+    /// Whether the policy lets `url` through. Unparseable URLs are never allowed.
+    pub fn allows(&self, url: &str) -> bool {
+        let Ok(url) = Url::parse(url) else {
+            return false;
+        };
+        let scheme = url.scheme();
+
+        match self {
+            Self::AllowAll => true,
+            Self::Allow(schemes) => schemes.iter().any(|s| s.eq_ignore_ascii_case(scheme)),
+            Self::Deny(schemes) => !schemes.iter().any(|s| s.eq_ignore_ascii_case(scheme)),
+        }
+    }
+}
+
+/// Payload of the [`URL_BLOCKED_EVENT`].
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UrlBlockedPayload {
+    /// Label of the window whose UI requested the URL.
+    pub label: String,
+    pub url: String,
+}
+
+/// Opens a URL with the system's default handler.
+///
+/// `new_tab` is ignored, desktop browsers decide on their own where to open the URL.
+pub fn open_url_in_browser(open_url: &egui::OpenUrl) {
+    if let Err(e) = webbrowser::open(&open_url.url) {
+        eprintln!("Failed to open URL {}: {}", open_url.url, e);
+    }
+}
+
+/// Applies the scheme policy and forwards allowed URLs to the configured handler.
+pub(crate) struct UrlOpener {
+    app: AppHandle,
+    policy: UrlSchemePolicy,
+    handler: Option<OpenUrlHandler>,
+}
+
+impl UrlOpener {
+    pub fn new(app: AppHandle, policy: UrlSchemePolicy, handler: Option<OpenUrlHandler>) -> Self {
+        Self {
+            app,
+            policy,
+            handler,
+        }
+    }
+
+    pub fn open(&self, label: &str, open_url: &egui::OpenUrl) {
+        if !self.policy.allows(&open_url.url) {
+            let payload = UrlBlockedPayload {
+                label: label.to_string(),
+                url: open_url.url.clone(),
+            };
+            if let Err(e) = self.app.emit(URL_BLOCKED_EVENT, payload) {
+                eprintln!("Failed to emit {}: {}", URL_BLOCKED_EVENT, e);
+            }
+            return;
+        }
+
+        match &self.handler {
+            Some(handler) => handler(&self.app, open_url),
+            None => open_url_in_browser(open_url),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_policy_allows_web_links() {
+        let policy = UrlSchemePolicy::default();
+        assert!(policy.allows("https://example.com"));
+        assert!(policy.allows("mailto:someone@example.com"));
+        assert!(!policy.allows("file:///etc/passwd"));
+    }
+
+    #[test]
+    fn denied_schemes_are_blocked() {
+        let policy = UrlSchemePolicy::Deny(vec!["javascript".into()]);
+        assert!(!policy.allows("javascript:alert(1)"));
+        assert!(policy.allows("https://example.com"));
+    }
+
+    #[test]
+    fn schemes_are_matched_ignoring_case() {
+        let policy = UrlSchemePolicy::Allow(vec!["HTTPS".into()]);
+        assert!(policy.allows("https://example.com"));
+        assert!(policy.allows("HTTPS://example.com"));
+
+        let policy = UrlSchemePolicy::Deny(vec!["File".into()]);
+        assert!(!policy.allows("FILE:///tmp"));
+    }
+
+    #[test]
+    fn malformed_urls_are_never_allowed() {
+        assert!(!UrlSchemePolicy::AllowAll.allows("not a url"));
+        assert!(!UrlSchemePolicy::Deny(Vec::new()).allows("://missing-scheme"));
+    }
+}
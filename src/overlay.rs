@@ -0,0 +1,219 @@
+//! egui layered over (or under) the webview of a Tauri window,
+//! see [`crate::AppHandleExt::start_egui_overlay`].
+//!
+//! Above the webview, egui draws into a separate OS window that follows the parent's
+//! client area, not into a layer of the parent window itself. While egui doesn't want
+//! the pointer, the overlay window lets it through to the webview and gets no cursor
+//! events, so the cursor is polled instead, and only while it's over the overlay.
+//! Polling restarts once the mouse moves (device events, Windows and macOS only) or
+//! the parent window reports the cursor. Where the cursor position can't be read
+//! (e.g. on Wayland), the overlay keeps the pointer instead of letting it through.
+
+use std::time::{Duration, Instant};
+
+use anyhow::Error;
+use tauri::window::WindowBuilder;
+use tauri::AppHandle;
+
+use crate::input::InputWants;
+
+/// Where an egui overlay sits relative to the webview of its window.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum OverlayLayer {
+    /// In a separate transparent window owned by the webview's window and kept over it,
+    /// labelled `<label>-egui-overlay`. The pointer passes through to the webview
+    /// wherever egui isn't drawing.
+    #[default]
+    Above,
+    /// In the window's own surface, behind its webviews. The webview has to be
+    /// transparent for egui to show through, and egui only gets the input it lets through.
+    Below,
+}
+
+/// How often to check whether the pointer moved back over egui while it passes through
+/// the overlay.
+const CURSOR_POLL_INTERVAL: Duration = Duration::from_millis(30);
+
+/// An egui window drawn as an overlay of the webview window `parent`.
+pub(crate) struct Overlay {
+    pub layer: OverlayLayer,
+    /// The window with the webview, the same as the egui window when below it
+    pub parent: tauri::Window,
+    /// Whether the pointer currently passes through to the webview
+    click_through: bool,
+    /// When to next check where the pointer is while it passes through,
+    /// `None` while it's outside the overlay
+    next_cursor_poll: Option<Instant>,
+    last_cursor_poll: Option<Instant>,
+    /// Where the last check found the pointer
+    last_cursor: Option<egui::Pos2>,
+    /// Set once the cursor position can't be read, the pointer then always goes to egui
+    cursor_unavailable: bool,
+}
+
+impl Overlay {
+    pub fn new(layer: OverlayLayer, parent: tauri::Window) -> Self {
+        Self {
+            layer,
+            parent,
+            click_through: false,
+            next_cursor_poll: None,
+            last_cursor_poll: None,
+            last_cursor: None,
+            cursor_unavailable: false,
+        }
+    }
+
+    /// Whether `label` is the webview window of this overlay's own child window.
+    pub fn follows(&self, label: &str) -> bool {
+        self.layer == OverlayLayer::Above && self.parent.label() == label
+    }
+
+    /// Moves the overlay window onto the parent's client area, hiding it with the parent.
+    pub fn follow_parent(&self, window: &tauri::Window) {
+        let bounds = self
+            .parent
+            .inner_position()
+            .and_then(|position| Ok((position, self.parent.inner_size()?)));
+        let result = match bounds {
+            Ok((position, size)) => window
+                .set_position(position)
+                .and_then(|()| window.set_size(size)),
+            Err(e) => Err(e),
+        };
+        if let Err(e) = result {
+            eprintln!("Failed to move overlay {}: {}", window.label(), e);
+        }
+
+        let shown = window.is_visible().unwrap_or(true);
+        let parent_shown = !self.parent.is_minimized().unwrap_or(false);
+        if shown != parent_shown {
+            let result = if parent_shown {
+                window.show()
+            } else {
+                window.hide()
+            };
+            result.ok();
+        }
+    }
+
+    /// Lets the pointer through to the webview unless egui wanted it in the last frame.
+    pub fn update_click_through(&mut self, window: &tauri::Window, wants: InputWants) {
+        if self.layer != OverlayLayer::Above || self.cursor_unavailable {
+            return;
+        }
+
+        let click_through = !wants.pointer;
+        if click_through != self.click_through {
+            self.set_click_through(window, click_through);
+        }
+    }
+
+    /// While the pointer passes through, the overlay window doesn't get any cursor events.
+    /// Returns where the cursor moved since the last poll, in points, for egui to tell
+    /// whether it's back over its UI.
+    ///
+    /// Polling stops once the cursor leaves the overlay, see [`Self::wake_cursor_poll`].
+    pub fn poll_cursor(
+        &mut self,
+        window: &tauri::Window,
+        pixels_per_point: f32,
+        now: Instant,
+    ) -> Option<egui::Pos2> {
+        match self.next_cursor_poll {
+            Some(next) if next <= now => {}
+            _ => return None,
+        }
+        self.last_cursor_poll = Some(now);
+
+        let bounds = window.cursor_position().and_then(|cursor| {
+            let origin = window.inner_position()?;
+            Ok((cursor, origin, window.inner_size()?))
+        });
+        let (cursor, origin, size) = match bounds {
+            Ok(bounds) => bounds,
+            Err(e) => {
+                // Without the cursor position there's no telling when egui wants it back
+                eprintln!(
+                    "Can't track the cursor over overlay {}, it keeps the pointer: {}",
+                    window.label(),
+                    e
+                );
+                self.cursor_unavailable = true;
+                self.next_cursor_poll = None;
+                self.set_click_through(window, false);
+                return None;
+            }
+        };
+
+        let x = cursor.x - origin.x as f64;
+        let y = cursor.y - origin.y as f64;
+        if x < 0.0 || y < 0.0 || x >= size.width as f64 || y >= size.height as f64 {
+            self.next_cursor_poll = None;
+            self.last_cursor = None;
+            return None;
+        }
+        self.next_cursor_poll = Some(now + CURSOR_POLL_INTERVAL);
+
+        let pos = egui::pos2(
+            (x / pixels_per_point as f64) as f32,
+            (y / pixels_per_point as f64) as f32,
+        );
+        if self.last_cursor == Some(pos) {
+            return None;
+        }
+        self.last_cursor = Some(pos);
+        Some(pos)
+    }
+
+    /// Polls the cursor again after it left the overlay, once the mouse moved or the
+    /// parent window saw the cursor. Polls at most every [`CURSOR_POLL_INTERVAL`].
+    pub fn wake_cursor_poll(&mut self, now: Instant) {
+        if !self.click_through || self.next_cursor_poll.is_some() {
+            return;
+        }
+        let next = match self.last_cursor_poll {
+            Some(last) => now.max(last + CURSOR_POLL_INTERVAL),
+            None => now,
+        };
+        self.next_cursor_poll = Some(next);
+    }
+
+    /// When the overlay next wants to check the cursor, see [`Self::poll_cursor`].
+    pub fn next_cursor_poll(&self) -> Option<Instant> {
+        self.next_cursor_poll
+    }
+
+    fn set_click_through(&mut self, window: &tauri::Window, click_through: bool) {
+        if let Err(e) = window.set_ignore_cursor_events(click_through) {
+            eprintln!(
+                "Failed to change click-through of overlay {}: {}",
+                window.label(),
+                e
+            );
+            return;
+        }
+        self.click_through = click_through;
+        self.next_cursor_poll = click_through.then(|| Instant::now() + CURSOR_POLL_INTERVAL);
+        self.last_cursor = None;
+    }
+}
+
+/// Opens the transparent window an overlay above the webview of `parent` draws into,
+/// see [`Overlay::follow_parent`] to move it into place.
+pub(crate) fn overlay_window(
+    app: &AppHandle,
+    parent: &tauri::Window,
+) -> Result<tauri::Window, Error> {
+    let label = format!("{}-egui-overlay", parent.label());
+    let window = WindowBuilder::new(app, label)
+        .parent(parent)?
+        .transparent(true)
+        .decorations(false)
+        .shadow(false)
+        .resizable(false)
+        .skip_taskbar(true)
+        .focused(false)
+        .build()?;
+    Ok(window)
+}
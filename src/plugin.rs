@@ -1,7 +1,10 @@
 use anyhow::Error;
+use std::cell::RefCell;
 use std::collections::HashMap;
-use std::sync::{Arc, Mutex};
-use std::time::Instant;
+use std::rc::Rc;
+use std::sync::{Arc, Mutex, OnceLock};
+use std::thread::{self, ThreadId};
+use std::time::{Duration, Instant};
 
 use tauri::{AppHandle, Manager, PhysicalSize};
 use tauri_runtime::window::CursorIcon;
@@ -10,49 +13,234 @@ use tauri_runtime::UserEvent;
 use tauri_runtime_wry::{Context, PluginBuilder, WindowMessage};
 use tauri_runtime_wry::{EventLoopIterationContext, Message, Plugin, WebContextStore};
 
-use tauri_runtime_wry::tao::event::{
-    ElementState, Event, KeyEvent, MouseButton, MouseScrollDelta, WindowEvent as TaoWindowEvent,
-};
+use tauri_runtime_wry::tao::event::{DeviceEvent, Event, WindowEvent as TaoWindowEvent};
 use tauri_runtime_wry::tao::event_loop::{ControlFlow, EventLoopProxy, EventLoopWindowTarget};
-use tauri_runtime_wry::tao::keyboard::{Key, KeyCode};
 
-use crate::renderer::Renderer;
-use crate::utils::{get_id_from_tao_id, get_label_from_tao_id};
+use crate::app::{EguiApp, Frame, Storage};
+use crate::clipboard::{self, Clipboard, SystemClipboard};
+use crate::handle::{EguiHandle, TaskSender};
+use crate::ime;
+use crate::input::{EguiInput, InputWants};
+use crate::native_textures::NativeTextures;
+use crate::open_url::{OpenUrlHandler, UrlOpener, UrlSchemePolicy};
+use crate::overlay::{self, Overlay, OverlayLayer};
+use crate::region::{self, EguiRegion};
+use crate::render_error::{RenderErrorHandler, RenderErrorPayload, RenderErrorReporter};
+use crate::renderer::{EguiRendererOptions, GpuContext, Renderer, SharedGpu};
+use crate::repaint::RepaintSchedule;
+use crate::utils::{
+    get_id_from_label, get_id_from_tao_id, get_label_from_id, get_label_from_tao_id,
+};
+use crate::viewport::{self, ViewportWindow};
 
 /// A map of EguiWindow instances, keyed by their Tauri window label.
-type EguiWindowMap = Arc<Mutex<HashMap<String, EguiWindow>>>;
+pub(crate) type EguiWindowMap = Rc<RefCell<HashMap<String, EguiWindow>>>;
+
+/// The child viewports of an egui window, shared with egui's immediate viewport renderer.
+type ViewportMap = Rc<RefCell<HashMap<egui::ViewportId, ViewportWindow>>>;
+
+thread_local! {
+    // Apps and renderers are tied to the event loop thread, so they live in a
+    // thread local rather than in Tauri state that any thread could reach.
+    static EGUI_WINDOWS: EguiWindowMap = Rc::default();
+}
+
+/// The egui windows of the current thread, only the event loop thread has any.
+pub(crate) fn egui_windows() -> EguiWindowMap {
+    EGUI_WINDOWS.with(Rc::clone)
+}
+
+/// The thread running the event loop, recorded on the plugin's first event.
+#[derive(Clone)]
+struct EventLoopThread {
+    recorded: Arc<OnceLock<ThreadId>>,
+    /// Asks the runtime instead until then, e.g. for windows started in `setup`
+    is_main_thread: Arc<dyn Fn() -> bool + Send + Sync>,
+}
+
+impl EventLoopThread {
+    fn new<T: UserEvent>(context: Context<T>) -> Self {
+        Self {
+            recorded: Arc::default(),
+            is_main_thread: Arc::new(move || {
+                context.run_threaded(|main_thread| main_thread.is_some())
+            }),
+        }
+    }
+
+    fn record(&self) {
+        self.recorded.get_or_init(|| thread::current().id());
+    }
+
+    fn is_current(&self) -> bool {
+        match self.recorded.get() {
+            Some(thread) => *thread == thread::current().id(),
+            None => (self.is_main_thread)(),
+        }
+    }
+}
+
+/// Renderer options for every window, keyed by label, on top of the defaults.
+struct RendererOptions {
+    default: EguiRendererOptions,
+    windows: HashMap<String, EguiRendererOptions>,
+}
+
+impl RendererOptions {
+    fn for_window(&self, label: &str) -> &EguiRendererOptions {
+        self.windows.get(label).unwrap_or(&self.default)
+    }
+}
+
+/// How long to wait before retrying a frame that failed to render.
+const SURFACE_RETRY_DELAY: Duration = Duration::from_millis(100);
+
+/// The storage apps save into when their window stops rendering egui.
+type EguiStorage = Arc<Mutex<Option<Box<dyn Storage>>>>;
 
 // The builder pattern is mandatorily needed for a Tauri `.wry_plugin()`
 // It sets up the tauri state + offers a hook into the event system
 pub struct Builder {
     app: AppHandle,
+    clipboard: Option<Box<dyn Clipboard>>,
+    url_scheme_policy: UrlSchemePolicy,
+    on_open_url: Option<OpenUrlHandler>,
+    on_render_error: Option<RenderErrorHandler>,
+    storage: Option<Box<dyn Storage>>,
+    renderer_options: RendererOptions,
 }
 
 impl Builder {
     pub fn new(app: AppHandle) -> Self {
-        Self { app }
+        Self {
+            app,
+            clipboard: None,
+            url_scheme_policy: UrlSchemePolicy::default(),
+            on_open_url: None,
+            on_render_error: None,
+            storage: None,
+            renderer_options: RendererOptions {
+                default: EguiRendererOptions::default(),
+                windows: HashMap::new(),
+            },
+        }
+    }
+
+    /// Use a custom clipboard backend instead of the system clipboard.
+    pub fn clipboard(mut self, clipboard: impl Clipboard + 'static) -> Self {
+        self.clipboard = Some(Box::new(clipboard));
+        self
+    }
+
+    /// Restrict which URL schemes egui is allowed to open.
+    /// Blocked URLs emit [`crate::URL_BLOCKED_EVENT`] instead.
+    pub fn url_scheme_policy(mut self, policy: UrlSchemePolicy) -> Self {
+        self.url_scheme_policy = policy;
+        self
+    }
+
+    /// Handle the URLs egui wants to open (e.g. from `ui.hyperlink`).
+    /// Defaults to opening them in the system browser, which can't be told to use
+    /// a new tab, so handle [`egui::OpenUrl::new_tab`] here if it matters.
+    pub fn on_open_url<F>(mut self, f: F) -> Self
+    where
+        F: Fn(&AppHandle, &egui::OpenUrl) + Send + 'static,
+    {
+        self.on_open_url = Some(Box::new(f));
+        self
+    }
+
+    /// Get told when a window keeps failing to render, e.g. after a GPU reset.
+    /// [`crate::RENDER_ERROR_EVENT`] is emitted either way.
+    pub fn on_render_error<F>(mut self, f: F) -> Self
+    where
+        F: Fn(&AppHandle, &RenderErrorPayload) + Send + 'static,
+    {
+        self.on_render_error = Some(Box::new(f));
+        self
+    }
+
+    /// Storage passed to [`EguiApp::save`] when a window stops rendering egui.
+    pub fn storage(mut self, storage: impl Storage + 'static) -> Self {
+        self.storage = Some(Box::new(storage));
+        self
+    }
+
+    /// How egui windows are rendered, also picks the GPU device they share.
+    pub fn renderer_options(mut self, options: EguiRendererOptions) -> Self {
+        self.renderer_options.default = options;
+        self
+    }
+
+    /// Overrides the renderer options for the window with the given label.
+    /// The shared device is picked by [`Self::renderer_options`], so the device
+    /// options (power preference, backends, features and limits) are ignored here.
+    pub fn window_renderer_options(mut self, label: &str, options: EguiRendererOptions) -> Self {
+        self.renderer_options
+            .windows
+            .insert(label.to_string(), options);
+        self
     }
 }
 
 impl<T: UserEvent> PluginBuilder<T> for Builder {
     type Plugin = EguiPlugin<T>;
 
-    fn build(self, _: Context<T>) -> Self::Plugin {
-        let egui_window_map: EguiWindowMap = Arc::new(Mutex::new(HashMap::new()));
-        self.app.manage(egui_window_map.clone());
-        EguiPlugin::new(egui_window_map)
+    fn build(self, context: Context<T>) -> Self::Plugin {
+        // Plugins may be built on any thread, the event loop thread is only known for sure
+        // once it calls the plugin
+        let event_loop_thread = EventLoopThread::new(context.clone());
+        self.app.manage(event_loop_thread.clone());
+
+        let storage: EguiStorage = Arc::new(Mutex::new(self.storage));
+        self.app.manage(storage.clone());
+
+        self.app.manage(TaskSender::new(context.proxy));
+
+        self.app
+            .manage(SharedGpu::new(self.renderer_options.default.clone()));
+        self.app.manage(self.renderer_options);
+
+        let clipboard = self
+            .clipboard
+            .unwrap_or_else(|| Box::new(SystemClipboard::new()));
+
+        let render_errors = RenderErrorReporter::new(self.app.clone(), self.on_render_error);
+        let url_opener = UrlOpener::new(self.app, self.url_scheme_policy, self.on_open_url);
+
+        EguiPlugin::new(
+            event_loop_thread,
+            storage,
+            clipboard,
+            url_opener,
+            render_errors,
+        )
     }
 }
 
 pub struct EguiPlugin<T: UserEvent> {
-    windows: EguiWindowMap,
+    event_loop_thread: EventLoopThread,
+    storage: EguiStorage,
+    clipboard: Box<dyn Clipboard>,
+    url_opener: UrlOpener,
+    render_errors: RenderErrorReporter,
     _phantom: std::marker::PhantomData<T>, // this does nothing, just keeps compiler happy
 }
 
 impl<T: UserEvent> EguiPlugin<T> {
-    fn new(windows: EguiWindowMap) -> Self {
+    fn new(
+        event_loop_thread: EventLoopThread,
+        storage: EguiStorage,
+        clipboard: Box<dyn Clipboard>,
+        url_opener: UrlOpener,
+        render_errors: RenderErrorReporter,
+    ) -> Self {
         Self {
-            windows,
+            event_loop_thread,
+            storage,
+            clipboard,
+            url_opener,
+            render_errors,
             _phantom: std::marker::PhantomData,
         }
     }
@@ -64,53 +252,114 @@ impl<T: UserEvent> Plugin<T> for EguiPlugin<T> {
         event: &Event<Message<T>>,
         _event_loop: &EventLoopWindowTarget<Message<T>>,
         proxy: &EventLoopProxy<Message<T>>,
-        _control_flow: &mut ControlFlow,
+        control_flow: &mut ControlFlow,
         context: EventLoopIterationContext<'_, T>,
         _: &WebContextStore,
     ) -> bool {
+        // Plugins are called on the event loop thread, which owns the egui windows
+        self.event_loop_thread.record();
+        let egui_windows = egui_windows();
+
         match event {
-            Event::WindowEvent {
-                event, window_id, ..
-            } => {
-                if let Some(label) = get_label_from_tao_id(window_id, &context) {
-                    let mut windows = self.windows.lock().unwrap();
-                    if let Some(egui_win) = windows.get_mut(&label) {
-                        match event {
-                            TaoWindowEvent::Resized(size) => {
-                                egui_win.size = PhysicalSize::new(size.width, size.height);
-                                egui_win.renderer.resize(size.width, size.height);
-                                return true;
-                            }
-                            _ => {
-                                let consumed = egui_win.handle_event(event);
-
-                                let win_id = get_id_from_tao_id(window_id, &context);
-
-                                // Request redraw after input events to process accumulated events
-                                if let Some(id) = win_id {
-                                    proxy
-                                        .send_event(Message::Window(
-                                            id,
-                                            WindowMessage::RequestRedraw,
-                                        ))
-                                        .ok();
-                                }
+            Event::NewEvents(_) => {
+                let mut windows = egui_windows.borrow_mut();
 
-                                // Request a redraw after any input event
-                                return consumed;
-                            }
+                // Windows closed since the last iteration are gone,
+                // the others had their close prevented and are drawn into again
+                let closed: Vec<String> = windows
+                    .iter_mut()
+                    .filter_map(|(label, egui_win)| {
+                        (!egui_win.reattach_surface()).then(|| label.clone())
+                    })
+                    .collect();
+                for label in closed {
+                    if let Some(egui_win) = windows.remove(&label) {
+                        egui_win.exit(&mut self.storage.lock().unwrap());
+                    }
+                }
+
+                // Wake up the windows whose scheduled repaint is due
+                let now = Instant::now();
+                for egui_win in windows.values_mut() {
+                    egui_win.poll_overlay_cursor(now);
+                    for viewport_id in egui_win.repaint.take_due(now) {
+                        if let Some(label) = egui_win.redraw_label(viewport_id) {
+                            request_redraw(&label, proxy, &context);
                         }
                     }
                 }
             }
+            // The pointer may be back over an overlay that let it through
+            Event::DeviceEvent {
+                event: DeviceEvent::MouseMotion { .. },
+                ..
+            } => {
+                let now = Instant::now();
+                for egui_win in egui_windows.borrow_mut().values_mut() {
+                    egui_win.wake_overlay_cursor_poll(now);
+                }
+            }
+            Event::RedrawEventsCleared => {
+                // Sleep until the earliest scheduled repaint instead of Tauri's plain `Wait`
+                let next_repaint = egui_windows
+                    .borrow()
+                    .values()
+                    .flat_map(|egui_win| {
+                        let overlay_poll =
+                            egui_win.overlay.as_ref().and_then(|o| o.next_cursor_poll());
+                        [egui_win.repaint.next(), overlay_poll]
+                    })
+                    .flatten()
+                    .min();
+                if let Some(next_repaint) = next_repaint {
+                    if *control_flow != ControlFlow::Exit {
+                        *control_flow = ControlFlow::WaitUntil(next_repaint);
+                        return true;
+                    }
+                }
+            }
+            Event::WindowEvent {
+                event, window_id, ..
+            } => {
+                if let Some(id) = get_id_from_tao_id(window_id, &context) {
+                    return self.handle_window_event(id, event, proxy, &context);
+                }
+            }
+            // `window.close()`, `window.destroy()`, `Frame::close` and `ViewportCommand::Close`
+            // skip tao's window events, Tauri closes (or destroys) the window right after us
+            Event::UserEvent(Message::Window(
+                id,
+                message @ (WindowMessage::Close | WindowMessage::Destroy),
+            )) => {
+                let event = match message {
+                    WindowMessage::Close => TaoWindowEvent::CloseRequested,
+                    _ => TaoWindowEvent::Destroyed,
+                };
+                return self.handle_window_event(*id, &event, proxy, &context);
+            }
             Event::RedrawRequested(window_id) => {
                 if let Some(label) = get_label_from_tao_id(window_id, &context) {
-                    let mut windows = self.windows.lock().unwrap();
+                    let mut windows = egui_windows.borrow_mut();
+
+                    if let Some((egui_win, viewport_id)) = find_viewport(&mut windows, &label) {
+                        self.redraw_viewport(egui_win, viewport_id, proxy, &context);
+                        return false;
+                    }
+
                     if let Some(egui_win) = windows.get_mut(&label) {
+                        // Nothing to draw into, `resume_if_shown` (or `reattach_surface`)
+                        // repaints once there is again
+                        if egui_win.is_hidden() || egui_win.surface_released {
+                            return false;
+                        }
+
+                        let frame_start = Instant::now();
+
                         // Get the egui context from the EguiWindow
-                        let raw_input = egui_win.take_egui_input();
+                        let raw_input = egui_win.input.take_egui_input();
+                        egui_win.set_immediate_viewport_renderer();
 
-                        // Run `ui_fn` (which describes the UI)
+                        // Run the app's `update` (which describes the UI)
                         // This function comes from the tauri app itself and runs every frame.
                         // The `ctx.run()` method processes the inputs and drawings and returns output:
                         // 1. texture info to give to GPU
@@ -121,47 +370,104 @@ impl<T: UserEvent> Plugin<T> for EguiPlugin<T> {
                             shapes,
                             pixels_per_point,
                             platform_output,
-                            ..
+                            mut viewport_output,
                         } = egui_win.context.run(raw_input, |ctx| {
-                            (egui_win.ui_fn)(ctx);
+                            egui_win.app.update(ctx, &mut egui_win.frame);
                         });
+                        let root_output = viewport_output.remove(&egui::ViewportId::ROOT);
 
                         // Handle platform output (clipboard, cursor, links)
+                        // and viewport commands (title, size, screenshots, ...)
                         if let Some(win_id) = get_id_from_tao_id(window_id, &context) {
-                            if let Err(e) =
-                                egui_win.handle_platform_output(&platform_output, win_id, &proxy)
-                            {
+                            if let Err(e) = egui_win.handle_platform_output(
+                                &platform_output,
+                                win_id,
+                                proxy,
+                                self.clipboard.as_mut(),
+                                &self.url_opener,
+                            ) {
                                 eprintln!("Error handling platform output: {}", e);
                             }
+
+                            if let Some(root_output) = &root_output {
+                                egui_win.handle_viewport_commands(
+                                    &root_output.commands,
+                                    win_id,
+                                    proxy,
+                                    self.clipboard.as_mut(),
+                                );
+                            }
                         }
 
+                        // Open, update and close the app's child viewports
+                        self.sync_viewports(egui_win, viewport_output, proxy, &context);
+
                         // Converts all the shapes into triangles meshes
-                        let paint_jobs = egui_win.context.tessellate(shapes, pixels_per_point);
+                        let paint_jobs = egui_win.tessellate(shapes, pixels_per_point);
+
+                        // Keep converting positions with whatever zoom the UI settled on
+                        egui_win.input.zoom_factor = egui_win.context.zoom_factor();
 
-                        let width = egui_win.size.width;
-                        let height = egui_win.size.height;
+                        // Decides which of the next events egui takes for itself
+                        egui_win.input.wants = InputWants::of(&egui_win.context);
+                        if let Some(overlay) = &mut egui_win.overlay {
+                            overlay.update_click_through(&egui_win.window, egui_win.input.wants);
+                        }
 
-                        let screen_descriptor = egui_wgpu::ScreenDescriptor {
-                            size_in_pixels: [width, height],
-                            pixels_per_point: pixels_per_point,
-                        };
+                        let screen_descriptor = egui_win.screen_descriptor(pixels_per_point);
+                        let clear_color = egui_win.clear_color();
 
                         // Finally we render textures, paint jobs, etc. using the GPU
-                        egui_win.renderer.render_frame(
+                        let size_in_pixels = screen_descriptor.size_in_pixels;
+                        let rendered = egui_win.renderer.render_frame(
                             screen_descriptor,
-                            paint_jobs,
+                            &paint_jobs,
                             textures_delta,
+                            clear_color,
                         );
+                        match rendered {
+                            Ok(()) => {
+                                egui_win.surface_errors = 0;
+                                egui_win.deliver_screenshots();
+                                egui_win.last_frame = Some(LastFrame {
+                                    paint_jobs,
+                                    size_in_pixels,
+                                    pixels_per_point,
+                                    clear_color,
+                                });
+                            }
+                            Err(e) => {
+                                egui_win.surface_errors += 1;
+                                self.render_errors
+                                    .report(&label, &e, egui_win.surface_errors);
 
-                        // Check if egui wants us to repaint and request another redraw
-                        if egui_win.context.has_requested_repaint() {
-                            let win_id = get_id_from_tao_id(window_id, &context);
-                            if let Some(id) = win_id {
-                                proxy
-                                    .send_event(Message::Window(id, WindowMessage::RequestRedraw))
-                                    .ok();
+                                // Skip this frame and try again shortly,
+                                // unless the GPU has run out of memory.
+                                if e != wgpu::SurfaceError::OutOfMemory {
+                                    egui_win
+                                        .repaint
+                                        .request_after(egui::ViewportId::ROOT, SURFACE_RETRY_DELAY);
+                                }
                             }
                         }
+
+                        // Schedule the next frame for when egui wants it,
+                        // the next `NewEvents` requests the redraw once it's due
+                        if let Some(root_output) = root_output {
+                            egui_win
+                                .repaint
+                                .request_after(egui::ViewportId::ROOT, root_output.repaint_delay);
+                        }
+
+                        let commands = egui_win.frame.finish(frame_start.elapsed().as_secs_f32());
+                        let window = egui_win.window.clone();
+
+                        // Commands may close the window or stop egui for it,
+                        // which needs the window map, so run them after releasing it.
+                        drop(windows);
+                        for command in commands {
+                            command(&window);
+                        }
                     }
                 }
             }
@@ -173,136 +479,695 @@ impl<T: UserEvent> Plugin<T> for EguiPlugin<T> {
     }
 }
 
-/// A collection egui context, renderer and a UI function
-struct EguiWindow {
+impl<T: UserEvent> EguiPlugin<T> {
+    /// Feeds an event of a Tauri window to the egui window (or child viewport) drawing into it.
+    /// Returns whether egui consumed it.
+    fn handle_window_event(
+        &mut self,
+        window_id: tauri_runtime::window::WindowId,
+        event: &TaoWindowEvent,
+        proxy: &EventLoopProxy<Message<T>>,
+        context: &EventLoopIterationContext<'_, T>,
+    ) -> bool {
+        let Some(label) = get_label_from_id(window_id, context) else {
+            return false;
+        };
+        let egui_windows = egui_windows();
+        let mut windows = egui_windows.borrow_mut();
+
+        // Overlays above a webview follow its window around
+        self.handle_overlay_parent_event(&mut windows, &label, event);
+
+        if let Some((egui_win, viewport_id)) = find_viewport(&mut windows, &label) {
+            return self.handle_viewport_event(egui_win, viewport_id, event, proxy, context);
+        }
+
+        // Tear down before Tauri drops the native window our surface draws into.
+        // Let the event through so Tauri can still destroy the window.
+        if let TaoWindowEvent::Destroyed = event {
+            if let Some(egui_win) = windows.remove(&label) {
+                egui_win.exit(&mut self.storage.lock().unwrap());
+            }
+            return false;
+        }
+
+        let Some(egui_win) = windows.get_mut(&label) else {
+            return false;
+        };
+        match event {
+            TaoWindowEvent::CloseRequested => {
+                // Tauri drops the native window unless the app prevents the close,
+                // so let go of the surface but keep egui until we know
+                egui_win.release_surface();
+                false
+            }
+            TaoWindowEvent::Resized(_) => {
+                let was_hidden = egui_win.is_hidden();
+                egui_win.input.handle_event(event, self.clipboard.as_mut());
+                egui_win.resize_surface();
+                egui_win.update_minimized();
+                egui_win.resume_if_shown(was_hidden);
+                // Never consumed, Tauri still has to resize the webviews
+                // and tell the app's listeners
+                false
+            }
+            TaoWindowEvent::Focused(_) => {
+                // Minimizing and restoring moves focus, but doesn't
+                // always resize (e.g. on macOS and Linux)
+                let was_hidden = egui_win.is_hidden();
+                egui_win.update_minimized();
+                egui_win.resume_if_shown(was_hidden);
+                false
+            }
+            _ => {
+                // egui sees every event, but the ones it has no use for
+                // are left to the webview and other plugins
+                let consumed = egui_win.input.handle_event(event, self.clipboard.as_mut())
+                    && egui_win.input.wants.event(event);
+
+                // Moving to a monitor with a different DPI resizes the window
+                if matches!(event, TaoWindowEvent::ScaleFactorChanged { .. }) {
+                    egui_win.resize_surface();
+                }
+
+                // Request a redraw after any input event to process accumulated events
+                proxy
+                    .send_event(Message::Window(window_id, WindowMessage::RequestRedraw))
+                    .ok();
+
+                consumed
+            }
+        }
+    }
+
+    /// Keeps the overlays above the webview of the window `label` on top of it,
+    /// and stops them along with it.
+    fn handle_overlay_parent_event(
+        &mut self,
+        windows: &mut HashMap<String, EguiWindow>,
+        label: &str,
+        event: &TaoWindowEvent,
+    ) {
+        let overlays: Vec<String> = windows
+            .iter()
+            .filter(|(_, egui_win)| {
+                egui_win
+                    .overlay
+                    .as_ref()
+                    .is_some_and(|overlay| overlay.follows(label))
+            })
+            .map(|(overlay_label, _)| overlay_label.clone())
+            .collect();
+
+        for overlay_label in overlays {
+            match event {
+                TaoWindowEvent::CloseRequested => {
+                    if let Some(egui_win) = windows.get_mut(&overlay_label) {
+                        egui_win.release_surface();
+                    }
+                }
+                TaoWindowEvent::Destroyed => {
+                    if let Some(egui_win) = windows.remove(&overlay_label) {
+                        stop_egui_window(egui_win, &mut self.storage.lock().unwrap());
+                    }
+                }
+                TaoWindowEvent::Moved(_)
+                | TaoWindowEvent::Resized(_)
+                | TaoWindowEvent::ScaleFactorChanged { .. }
+                | TaoWindowEvent::Focused(_) => {
+                    let egui_win = windows.get_mut(&overlay_label).unwrap();
+                    if let Some(overlay) = &egui_win.overlay {
+                        overlay.follow_parent(&egui_win.window);
+                    }
+                    egui_win.wake_overlay_cursor_poll(Instant::now());
+                }
+                TaoWindowEvent::CursorEntered { .. } | TaoWindowEvent::CursorMoved { .. } => {
+                    if let Some(egui_win) = windows.get_mut(&overlay_label) {
+                        egui_win.wake_overlay_cursor_poll(Instant::now());
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// Feeds an event of a child viewport's window into its input.
+    fn handle_viewport_event(
+        &mut self,
+        egui_win: &mut EguiWindow,
+        viewport_id: egui::ViewportId,
+        event: &TaoWindowEvent,
+        proxy: &EventLoopProxy<Message<T>>,
+        context: &EventLoopIterationContext<'_, T>,
+    ) -> bool {
+        let consumed = {
+            let mut viewports = egui_win.viewports.borrow_mut();
+            let Some(viewport) = viewports.get_mut(&viewport_id) else {
+                return false;
+            };
+
+            match event {
+                // Keep the window, the app closes the viewport by no longer showing it
+                TaoWindowEvent::CloseRequested => {
+                    viewport.input.request_close();
+                    true
+                }
+                TaoWindowEvent::Destroyed => {
+                    viewports.remove(&viewport_id);
+                    return false;
+                }
+                _ => {
+                    let consumed = viewport.input.handle_event(event, self.clipboard.as_mut());
+                    if matches!(
+                        event,
+                        TaoWindowEvent::Resized(_) | TaoWindowEvent::ScaleFactorChanged { .. }
+                    ) {
+                        viewport.resize_surface();
+                    }
+                    consumed
+                }
+            }
+        };
+
+        if let Some(label) = egui_win.redraw_label(viewport_id) {
+            request_redraw(&label, proxy, context);
+        }
+        consumed
+    }
+
+    /// Runs and renders a deferred child viewport. Immediate viewports are
+    /// drawn by their parent, so the parent gets redrawn instead.
+    fn redraw_viewport(
+        &mut self,
+        egui_win: &mut EguiWindow,
+        viewport_id: egui::ViewportId,
+        proxy: &EventLoopProxy<Message<T>>,
+        context: &EventLoopIterationContext<'_, T>,
+    ) {
+        // Taken out of the map while running, as immediate viewports it shows need the map
+        let Some(mut viewport) = egui_win.viewports.borrow_mut().remove(&viewport_id) else {
+            return;
+        };
+        let Some(ui) = viewport.ui.clone() else {
+            let parent = viewport.parent;
+            egui_win
+                .viewports
+                .borrow_mut()
+                .insert(viewport_id, viewport);
+            if let Some(label) = egui_win.redraw_label(parent) {
+                request_redraw(&label, proxy, context);
+            }
+            return;
+        };
+        if viewport.is_hidden() {
+            egui_win
+                .viewports
+                .borrow_mut()
+                .insert(viewport_id, viewport);
+            return;
+        }
+
+        egui_win.set_immediate_viewport_renderer();
+        let mut viewport_output = viewport.run(&egui_win.context, |ctx| ui(ctx));
+        egui_win
+            .viewports
+            .borrow_mut()
+            .insert(viewport_id, viewport);
+
+        // The last viewport to run carries the commands for all of them
+        if let Some(root_output) = viewport_output.remove(&egui::ViewportId::ROOT) {
+            if let Some(win_id) = get_id_from_label(&egui_win.label, context) {
+                egui_win.handle_viewport_commands(
+                    &root_output.commands,
+                    win_id,
+                    proxy,
+                    self.clipboard.as_mut(),
+                );
+            }
+            egui_win
+                .repaint
+                .request_after(egui::ViewportId::ROOT, root_output.repaint_delay);
+        }
+        self.sync_viewports(egui_win, viewport_output, proxy, context);
+    }
+
+    /// Brings the child viewports in line with egui's output: opens windows for new
+    /// deferred viewports, updates the existing ones and closes the ones no longer shown.
+    fn sync_viewports(
+        &mut self,
+        egui_win: &mut EguiWindow,
+        viewport_output: egui::ViewportIdMap<egui::ViewportOutput>,
+        proxy: &EventLoopProxy<Message<T>>,
+        context: &EventLoopIterationContext<'_, T>,
+    ) {
+        let closed: Vec<ViewportWindow> = {
+            let mut viewports = egui_win.viewports.borrow_mut();
+            let closed_ids: Vec<egui::ViewportId> = viewports
+                .keys()
+                .filter(|viewport_id| !viewport_output.contains_key(viewport_id))
+                .copied()
+                .collect();
+            closed_ids
+                .iter()
+                .filter_map(|viewport_id| viewports.remove(viewport_id))
+                .collect()
+        };
+        for viewport in closed {
+            close_viewport(viewport);
+        }
+
+        for (viewport_id, output) in viewport_output {
+            if viewport_id == egui::ViewportId::ROOT {
+                continue;
+            }
+
+            // Immediate viewports are opened while their parent runs
+            let is_open = egui_win.viewports.borrow().contains_key(&viewport_id);
+            if !is_open && output.class == egui::ViewportClass::Deferred {
+                match egui_win.open_viewport(viewport_id, output.parent, output.builder.clone()) {
+                    Ok(viewport) => {
+                        egui_win
+                            .viewports
+                            .borrow_mut()
+                            .insert(viewport_id, viewport);
+                        egui_win.repaint.request_after(viewport_id, Duration::ZERO);
+                    }
+                    Err(e) => {
+                        eprintln!(
+                            "Failed to open a window for viewport {:?}: {}",
+                            viewport_id, e
+                        );
+                        continue;
+                    }
+                }
+            }
+
+            let mut viewports = egui_win.viewports.borrow_mut();
+            let Some(viewport) = viewports.get_mut(&viewport_id) else {
+                continue;
+            };
+
+            let repaint_delay = output.repaint_delay;
+            let commands = viewport.update(output);
+            if let Some(win_id) = get_id_from_label(&viewport.label, context) {
+                let platform_output = std::mem::take(&mut viewport.platform_output);
+                handle_output_commands(
+                    &viewport.label,
+                    &platform_output,
+                    win_id,
+                    proxy,
+                    self.clipboard.as_mut(),
+                    &self.url_opener,
+                );
+
+                for command in commands {
+                    // Read back from the viewport's own renderer, like the root's screenshots
+                    if let egui::ViewportCommand::Screenshot(user_data) = command {
+                        viewport.request_screenshot(user_data);
+                        egui_win.context.request_repaint_of(viewport_id);
+                        continue;
+                    }
+                    if handle_viewport_command(
+                        &viewport.label,
+                        command,
+                        &mut viewport.input,
+                        win_id,
+                        proxy,
+                        self.clipboard.as_mut(),
+                    ) {
+                        egui_win.context.request_repaint_of(viewport_id);
+                    }
+                }
+            }
+
+            if let Some(e) = viewport.render_error.take() {
+                self.render_errors
+                    .report(&viewport.label, &e, viewport.surface_errors);
+                if e != wgpu::SurfaceError::OutOfMemory {
+                    egui_win
+                        .repaint
+                        .request_after(viewport_id, SURFACE_RETRY_DELAY);
+                }
+            }
+            egui_win.repaint.request_after(viewport_id, repaint_delay);
+        }
+    }
+}
+
+/// The state of an egui window after it was removed from the plugin,
+/// e.g. to persist `egui::Memory` or reuse the app elsewhere.
+///
+/// Apps don't have to be `Send`, so the state stays on the event loop thread:
+///
+/// ```compile_fail,E0277
+/// fn assert_send<T: Send>() {}
+/// assert_send::<tauri_plugin_egui::EguiWindowState>();
+/// ```
+pub struct EguiWindowState {
+    pub label: String,
+    pub context: egui::Context,
+    pub app: Box<dyn EguiApp>,
+}
+
+/// What the last successfully rendered frame of an egui window was drawn from.
+struct LastFrame {
+    paint_jobs: Vec<egui::epaint::ClippedPrimitive>,
+    size_in_pixels: [u32; 2],
+    pixels_per_point: f32,
+    clear_color: wgpu::Color,
+}
+
+/// A collection egui context, renderer and the app drawing the UI
+pub(crate) struct EguiWindow {
+    label: String,
+    window: tauri::Window,
     context: egui::Context,
     renderer: Renderer,
-    size: PhysicalSize<u32>,
-    ui_fn: Box<dyn FnMut(&egui::Context)>,
-    start_time: Instant,
-    egui_input: egui::RawInput,
-    pointer_pos: Option<egui::Pos2>,
-    scale_factor: f32,
-    modifiers: egui::Modifiers,
+    input: EguiInput,
+    repaint: RepaintSchedule,
+    app: Box<dyn EguiApp>,
+    frame: Frame,
+    ime_allowed: bool,
+    ime_cursor_area: Option<egui::Rect>,
+    /// Frames in a row that failed to render
+    surface_errors: u32,
+    minimized: bool,
+    /// Set while a close request may still drop the native window, see [`Self::release_surface`]
+    surface_released: bool,
+    /// `ViewportCommand::Screenshot`s waiting for a rendered frame
+    pending_screenshots: Vec<egui::UserData>,
+    /// Drawn again by [`AppHandleExt::capture_egui_window`]
+    last_frame: Option<LastFrame>,
+    /// Child viewports the app shows, each in a window of its own
+    viewports: ViewportMap,
+    /// Registered in the renderers of the window and its viewports alike
+    native_textures: NativeTextures,
+    /// Set if egui is drawn over (or under) a webview, see [`AppHandleExt::start_egui_overlay`]
+    overlay: Option<Overlay>,
+    /// The part of the window egui draws into, see [`AppHandleExt::start_egui_for_window_in_region`]
+    region: Option<EguiRegion>,
 }
 
-unsafe impl Send for EguiWindow {}
-unsafe impl Sync for EguiWindow {}
-
 impl EguiWindow {
-    fn handle_event(&mut self, event: &TaoWindowEvent) -> bool {
-        match event {
-            TaoWindowEvent::CursorMoved { position, .. } => {
-                let pos = egui::Pos2::new(
-                    position.x as f32 / self.scale_factor,
-                    position.y as f32 / self.scale_factor,
-                );
-                self.pointer_pos = Some(pos);
-                self.egui_input.events.push(egui::Event::PointerMoved(pos));
-                true
-            }
-            TaoWindowEvent::ModifiersChanged(modifiers) => {
-                self.modifiers = egui::Modifiers {
-                    alt: modifiers.alt_key(),
-                    ctrl: modifiers.control_key(),
-                    shift: modifiers.shift_key(),
-                    #[cfg(target_os = "macos")]
-                    mac_cmd: modifiers.super_key(),
-                    #[cfg(target_os = "macos")]
-                    command: modifiers.super_key(),
-                    #[cfg(not(target_os = "macos"))]
-                    mac_cmd: false,
-                    #[cfg(not(target_os = "macos"))]
-                    command: modifiers.control_key(),
-                };
-                self.egui_input.modifiers = self.modifiers;
-                true
-            }
-            TaoWindowEvent::MouseInput { state, button, .. } => {
-                let pressed = *state == ElementState::Pressed;
-                let button = match button {
-                    MouseButton::Left => egui::PointerButton::Primary,
-                    MouseButton::Right => egui::PointerButton::Secondary,
-                    MouseButton::Middle => egui::PointerButton::Middle,
-                    _ => return false,
-                };
+    pub(crate) fn context(&self) -> &egui::Context {
+        &self.context
+    }
 
-                // Use current pointer position, or default to (0,0) if not set
-                let pos = self.pointer_pos.unwrap_or(egui::Pos2::ZERO);
-
-                self.egui_input.events.push(egui::Event::PointerButton {
-                    pos,
-                    button,
-                    pressed,
-                    modifiers: self.modifiers,
-                });
-                true
-            }
-            TaoWindowEvent::MouseWheel { delta, .. } => {
-                let (x, y) = match delta {
-                    MouseScrollDelta::LineDelta(x, y) => (*x * 60.0, *y * 60.0),
-                    MouseScrollDelta::PixelDelta(pos) => (
-                        pos.x as f32 / self.scale_factor,
-                        pos.y as f32 / self.scale_factor,
+    pub(crate) fn inject_event(&mut self, event: egui::Event) {
+        self.input.push_event(event);
+        self.context.request_repaint();
+    }
+
+    pub(crate) fn native_textures(&self) -> &NativeTextures {
+        &self.native_textures
+    }
+
+    pub(crate) fn with_callback_resources(
+        &self,
+        f: impl FnOnce(&mut egui_wgpu::CallbackResources),
+    ) {
+        let egui_renderer = self.renderer.egui_renderer();
+        f(&mut egui_renderer.write().unwrap().callback_resources);
+    }
+
+    /// Feeds the cursor position to egui while an overlay lets the pointer through,
+    /// so the next frame can tell whether it's back over egui.
+    fn poll_overlay_cursor(&mut self, now: Instant) {
+        let Some(overlay) = &mut self.overlay else {
+            return;
+        };
+        if let Some(pos) = overlay.poll_cursor(&self.window, self.input.pixels_per_point(), now) {
+            // Only run a frame once the cursor is over one of egui's areas
+            let pos = pos - self.input.region_offset();
+            if self.context.layer_id_at(pos).is_some() {
+                self.inject_event(egui::Event::PointerMoved(pos));
+            }
+        }
+    }
+
+    /// Checks the cursor again after it left the overlay, see [`Overlay::wake_cursor_poll`].
+    fn wake_overlay_cursor_poll(&mut self, now: Instant) {
+        if let Some(overlay) = &mut self.overlay {
+            overlay.wake_cursor_poll(now);
+        }
+    }
+
+    /// Drops the surface before a close request that isn't prevented drops the native
+    /// window, egui keeps running until [`Self::reattach_surface`] knows whether it did.
+    fn release_surface(&mut self) {
+        self.renderer.detach_surface();
+        self.surface_released = true;
+    }
+
+    /// Draws into the window again if its close was prevented.
+    /// Returns false if the native window is gone.
+    fn reattach_surface(&mut self) -> bool {
+        if !self.surface_released {
+            return true;
+        }
+        // Fails once Tauri dropped the native window
+        if self.window.inner_size().is_err() {
+            return false;
+        }
+
+        let app = self.frame.app_handle();
+        let Some(shared_gpu) = app.try_state::<SharedGpu>() else {
+            return false;
+        };
+        let window = self.window.clone();
+        let attached =
+            tauri::async_runtime::block_on(self.renderer.attach_surface(window, &shared_gpu));
+        if let Err(e) = attached {
+            eprintln!("Failed to draw into {} again: {}", self.label, e);
+            return false;
+        }
+
+        self.surface_released = false;
+        self.resize_surface();
+        self.context.request_repaint();
+        true
+    }
+
+    /// Resizes the surface to the window, along with the region egui draws into.
+    fn resize_surface(&mut self) {
+        let PhysicalSize { width, height } = self.input.size;
+        self.renderer.resize(width, height);
+        self.update_region();
+    }
+
+    fn update_region(&mut self) {
+        self.input.region = self.region.as_ref().map(|region| {
+            region.bounds(self.input.size, self.input.native_pixels_per_point as f64)
+        });
+    }
+
+    /// Converts the shapes into triangle meshes, placed in the window's region.
+    fn tessellate(
+        &self,
+        mut shapes: Vec<egui::epaint::ClippedShape>,
+        pixels_per_point: f32,
+    ) -> Vec<egui::epaint::ClippedPrimitive> {
+        // The surface is cleared to transparent, only the region gets the app's clear color
+        if self.region.is_some() {
+            let [r, g, b, a] = self
+                .app
+                .clear_color(&self.context.style().visuals)
+                .map(|channel| (channel.clamp(0.0, 1.0) * 255.0).round() as u8);
+            let screen_rect = self.context.screen_rect();
+            shapes.insert(
+                0,
+                egui::epaint::ClippedShape {
+                    clip_rect: screen_rect,
+                    shape: egui::Shape::rect_filled(
+                        screen_rect,
+                        0.0,
+                        egui::Color32::from_rgba_unmultiplied(r, g, b, a),
                     ),
-                    _ => (0.0, 0.0),
-                };
-                self.egui_input.events.push(egui::Event::MouseWheel {
-                    unit: egui::MouseWheelUnit::Point,
-                    delta: egui::Vec2::new(x, y),
-                    modifiers: self.modifiers,
-                });
-                true
-            }
-            TaoWindowEvent::KeyboardInput { event, .. } => self.handle_keyboard_event(event),
-            _ => false,
-        }
-    }
-
-    fn handle_keyboard_event(&mut self, event: &KeyEvent) -> bool {
-        let pressed = event.state == ElementState::Pressed;
-        let mut handled = false;
-
-        // Handle text input from the text field
-        if pressed {
-            if let Some(text) = &event.text {
-                if !text.is_empty() {
-                    // Filter out control characters
-                    let filtered: String = text
-                        .chars()
-                        .filter(|c| !c.is_control() || *c == '\t' || *c == '\n' || *c == '\r')
-                        .collect();
-
-                    if !filtered.is_empty() {
-                        self.egui_input.events.push(egui::Event::Text(filtered));
-                        handled = true;
+                },
+            );
+        }
+
+        let mut paint_jobs = self.context.tessellate(shapes, pixels_per_point);
+        region::translate_paint_jobs(&mut paint_jobs, self.input.region_offset());
+        paint_jobs
+    }
+
+    /// Whether there is nothing to render into, e.g. while minimized.
+    ///
+    /// tao doesn't report occlusion, so windows covered by others keep rendering.
+    fn is_hidden(&self) -> bool {
+        self.minimized || self.input.size.width == 0 || self.input.size.height == 0
+    }
+
+    fn screen_descriptor(&self, pixels_per_point: f32) -> egui_wgpu::ScreenDescriptor {
+        egui_wgpu::ScreenDescriptor {
+            size_in_pixels: [self.input.size.width, self.input.size.height],
+            pixels_per_point,
+        }
+    }
+
+    /// The color the whole surface is cleared with, see [`Self::tessellate`] for regions.
+    fn clear_color(&self) -> wgpu::Color {
+        if self.region.is_some() {
+            return wgpu::Color::TRANSPARENT;
+        }
+        let [r, g, b, a] = self.app.clear_color(&self.context.style().visuals);
+        wgpu::Color {
+            r: r as f64,
+            g: g as f64,
+            b: b as f64,
+            a: a as f64,
+        }
+    }
+
+    /// Renders the last frame again offscreen, so the app doesn't run an extra frame
+    /// whose output would have to be handled outside the event loop.
+    fn capture(&mut self) -> Result<egui::ColorImage, Error> {
+        if self.is_hidden() {
+            return Err(Error::msg(format!(
+                "The egui window {} is minimized or has no size, there's nothing to capture.",
+                self.label
+            )));
+        }
+        let Some(last_frame) = &self.last_frame else {
+            return Err(Error::msg(format!(
+                "The egui window {} hasn't rendered a frame yet.",
+                self.label
+            )));
+        };
+
+        // Textures were uploaded when the frame was first rendered
+        let screen_descriptor = egui_wgpu::ScreenDescriptor {
+            size_in_pixels: last_frame.size_in_pixels,
+            pixels_per_point: last_frame.pixels_per_point,
+        };
+        self.renderer.capture_frame(
+            screen_descriptor,
+            &last_frame.paint_jobs,
+            egui::TexturesDelta::default(),
+            last_frame.clear_color,
+        )
+    }
+
+    /// Carries out the app's viewport commands, on the window or as input for the next frame.
+    ///
+    /// Screenshots are read back once this frame is rendered, requests stay pending
+    /// until a frame renders successfully.
+    fn handle_viewport_commands(
+        &mut self,
+        commands: &[egui::ViewportCommand],
+        window_id: tauri_runtime::window::WindowId,
+        proxy: &EventLoopProxy<Message<impl UserEvent>>,
+        clipboard: &mut dyn Clipboard,
+    ) {
+        for command in commands {
+            match command {
+                egui::ViewportCommand::Screenshot(user_data) => {
+                    self.pending_screenshots.push(user_data.clone());
+                }
+                command => {
+                    if handle_viewport_command(
+                        &self.label,
+                        command.clone(),
+                        &mut self.input,
+                        window_id,
+                        proxy,
+                        clipboard,
+                    ) {
+                        self.context.request_repaint();
                     }
                 }
             }
         }
 
-        // Handle key events (logical key first, then physical key fallback)
-        if let Some(key) = translate_logical_key(&event.logical_key) {
-            self.egui_input.events.push(egui::Event::Key {
-                key,
-                physical_key: None,
-                pressed,
-                repeat: event.repeat,
-                modifiers: self.modifiers,
-            });
-            handled = true;
-        } else if let Some(key) = translate_physical_key(&event.physical_key) {
-            self.egui_input.events.push(egui::Event::Key {
-                key,
-                physical_key: None,
-                pressed,
-                repeat: event.repeat,
-                modifiers: self.modifiers,
+        if !self.pending_screenshots.is_empty() {
+            self.renderer.request_screenshot();
+        }
+    }
+
+    /// Hands the screenshot of the rendered frame to the app in the next frame's input.
+    fn deliver_screenshots(&mut self) {
+        let image = match self.renderer.take_screenshot() {
+            Some(Ok(image)) => Arc::new(image),
+            Some(Err(e)) => {
+                eprintln!("Failed to take a screenshot of {}: {}", self.label, e);
+                self.pending_screenshots.clear();
+                return;
+            }
+            None => return,
+        };
+
+        for user_data in self.pending_screenshots.drain(..) {
+            self.input.push_event(egui::Event::Screenshot {
+                viewport_id: egui::ViewportId::ROOT,
+                user_data,
+                image: image.clone(),
             });
-            handled = true;
         }
+        self.context.request_repaint();
+    }
+
+    /// The window to redraw for a viewport, immediate viewports are drawn by their parent.
+    fn redraw_label(&self, mut viewport_id: egui::ViewportId) -> Option<String> {
+        let viewports = self.viewports.borrow();
+        loop {
+            if viewport_id == egui::ViewportId::ROOT {
+                return Some(self.label.clone());
+            }
+            let viewport = viewports.get(&viewport_id)?;
+            if viewport.ui.is_some() {
+                return Some(viewport.label.clone());
+            }
+            viewport_id = viewport.parent;
+        }
+    }
 
-        handled
+    /// Lets egui draw immediate viewports into their own windows while this window's app runs.
+    fn set_immediate_viewport_renderer(&self) {
+        let app = self.frame.app_handle().clone();
+        let label = self.label.clone();
+        let viewports = self.viewports.clone();
+        let native_textures = self.native_textures.clone();
+        egui::Context::set_immediate_viewport_renderer(move |context, immediate| {
+            show_immediate_viewport(
+                &app,
+                &label,
+                &viewports,
+                &native_textures,
+                context,
+                immediate,
+            );
+        });
+    }
+
+    fn open_viewport(
+        &self,
+        viewport_id: egui::ViewportId,
+        parent: egui::ViewportId,
+        builder: egui::ViewportBuilder,
+    ) -> Result<ViewportWindow, Error> {
+        let zoom_factor = self.context.zoom_factor();
+        open_viewport(
+            self.frame.app_handle(),
+            &self.label,
+            viewport_id,
+            parent,
+            builder,
+            zoom_factor,
+            &self.native_textures,
+        )
+    }
+
+    fn update_minimized(&mut self) {
+        self.minimized = self.window.is_minimized().unwrap_or(false);
+    }
+
+    /// Forces a repaint when the window becomes visible again,
+    /// as frames were skipped while it was hidden.
+    fn resume_if_shown(&mut self, was_hidden: bool) {
+        if was_hidden && !self.is_hidden() {
+            self.context.request_repaint();
+        }
     }
 
     fn handle_platform_output(
@@ -310,163 +1175,279 @@ impl EguiWindow {
         platform_output: &egui::PlatformOutput,
         window_id: tauri_runtime::window::WindowId,
         proxy: &EventLoopProxy<Message<impl UserEvent>>,
+        clipboard: &mut dyn Clipboard,
+        url_opener: &UrlOpener,
     ) -> Result<(), Error> {
-        // Handle cursor changes
-        let cursor_icon = platform_output.cursor_icon;
-        let tauri_cursor = egui_cursor_to_tauri_cursor(cursor_icon);
-
-        if let Err(e) = proxy.send_event(Message::Window(
+        handle_output_commands(
+            &self.label,
+            platform_output,
             window_id,
-            WindowMessage::SetCursorIcon(tauri_cursor),
-        )) {
-            eprintln!("Failed to send cursor message: {}", e);
-        }
+            proxy,
+            clipboard,
+            url_opener,
+        );
 
-        // Handle commands (clipboard, URL opening, etc.)
-        for command in &platform_output.commands {
-            match command {
-                egui::output::OutputCommand::CopyText(text) => {
-                    // TODO: Set clipboard content
-                    // For now, just print to console as a placeholder
-                    println!("Clipboard copy text requested: {}", text);
+        // Handle IME (Input Method Editor) state and candidate window positioning,
+        // which only takes effect on Windows (see `ime`).
+        // egui only reports `ime` while a text field has keyboard focus.
+        match platform_output.ime {
+            Some(ime_output) => {
+                if !self.ime_allowed {
+                    ime::set_ime_allowed(&self.window, true);
+                    self.ime_allowed = true;
                 }
-                egui::output::OutputCommand::CopyImage(image) => {
-                    // TODO: Set clipboard image content
-                    // For now, just print to console as a placeholder
-                    println!(
-                        "Clipboard copy image requested: {}x{}",
-                        image.width(),
-                        image.height()
-                    );
+
+                let cursor_rect = ime_output.cursor_rect.translate(self.input.region_offset());
+                let cursor_area = egui::Rect::from_min_max(
+                    cursor_rect.min * self.input.pixels_per_point(),
+                    cursor_rect.max * self.input.pixels_per_point(),
+                );
+                if self.ime_cursor_area != Some(cursor_area) {
+                    ime::set_ime_cursor_area(&self.window, cursor_area);
+                    self.ime_cursor_area = Some(cursor_area);
                 }
-                egui::output::OutputCommand::OpenUrl(url) => {
-                    // TODO: Open URL in default browser
-                    // For now, just print to console as a placeholder
-                    println!(
-                        "URL open requested: {} (target: {:?})",
-                        url.url, url.new_tab
-                    );
+            }
+            None => {
+                if self.ime_allowed {
+                    ime::set_ime_allowed(&self.window, false);
+                    self.ime_allowed = false;
+                    self.ime_cursor_area = None;
                 }
             }
         }
 
-        // Handle IME (Input Method Editor) positioning
-        if let Some(ime_pos) = platform_output.ime {
-            // TODO: Set IME position
-            // For now, just print to console as a placeholder
-            println!("IME position requested: {:?}", ime_pos);
+        Ok(())
+    }
+
+    /// Lets the app save and clean up, then drops the renderer (and its surface)
+    /// keeping what the caller may want back.
+    fn exit(mut self, storage: &mut Option<Box<dyn Storage>>) -> EguiWindowState {
+        for (_, viewport) in self.viewports.borrow_mut().drain() {
+            close_viewport(viewport);
+        }
+
+        if let Some(storage) = storage.as_deref_mut() {
+            self.app.save(storage);
+            storage.flush();
         }
+        self.app.on_exit();
 
-        Ok(())
+        EguiWindowState {
+            label: self.label,
+            context: self.context,
+            app: self.app,
+        }
+    }
+}
+
+/// Stops egui in the window, closing the window too if it was opened for an overlay.
+fn stop_egui_window(
+    egui_win: EguiWindow,
+    storage: &mut Option<Box<dyn Storage>>,
+) -> EguiWindowState {
+    let overlay_window = egui_win
+        .overlay
+        .as_ref()
+        .filter(|overlay| overlay.layer == OverlayLayer::Above)
+        .map(|_| egui_win.window.clone());
+
+    // Drops the surface before the window it draws into
+    let state = egui_win.exit(storage);
+    if let Some(window) = overlay_window {
+        if let Err(e) = window.destroy() {
+            eprintln!("Failed to close overlay window {}: {}", window.label(), e);
+        }
     }
+    state
+}
 
-    fn take_egui_input(&mut self) -> egui::RawInput {
-        let mut input = std::mem::take(&mut self.egui_input);
-        input.time = Some(self.start_time.elapsed().as_secs_f64());
-        input.screen_rect = Some(egui::Rect::from_min_size(
-            egui::Pos2::ZERO,
-            egui::Vec2::new(
-                self.size.width as f32 / self.scale_factor,
-                self.size.height as f32 / self.scale_factor,
-            ),
-        ));
-        input
-    }
-}
-
-fn translate_logical_key(key: &Key) -> Option<egui::Key> {
-    match key {
-        Key::Character(ch) => {
-            let ch = ch.chars().next()?;
-            match ch {
-                'a'..='z' | 'A'..='Z' => {
-                    let key_char = ch.to_ascii_uppercase();
-                    match key_char {
-                        'A' => Some(egui::Key::A),
-                        'B' => Some(egui::Key::B),
-                        'C' => Some(egui::Key::C),
-                        'D' => Some(egui::Key::D),
-                        'E' => Some(egui::Key::E),
-                        'F' => Some(egui::Key::F),
-                        'G' => Some(egui::Key::G),
-                        'H' => Some(egui::Key::H),
-                        'I' => Some(egui::Key::I),
-                        'J' => Some(egui::Key::J),
-                        'K' => Some(egui::Key::K),
-                        'L' => Some(egui::Key::L),
-                        'M' => Some(egui::Key::M),
-                        'N' => Some(egui::Key::N),
-                        'O' => Some(egui::Key::O),
-                        'P' => Some(egui::Key::P),
-                        'Q' => Some(egui::Key::Q),
-                        'R' => Some(egui::Key::R),
-                        'S' => Some(egui::Key::S),
-                        'T' => Some(egui::Key::T),
-                        'U' => Some(egui::Key::U),
-                        'V' => Some(egui::Key::V),
-                        'W' => Some(egui::Key::W),
-                        'X' => Some(egui::Key::X),
-                        'Y' => Some(egui::Key::Y),
-                        'Z' => Some(egui::Key::Z),
-                        _ => None,
+/// Sets the cursor and carries out egui's output commands (clipboard, links) for a window.
+fn handle_output_commands(
+    label: &str,
+    platform_output: &egui::PlatformOutput,
+    window_id: tauri_runtime::window::WindowId,
+    proxy: &EventLoopProxy<Message<impl UserEvent>>,
+    clipboard: &mut dyn Clipboard,
+    url_opener: &UrlOpener,
+) {
+    // Handle cursor changes
+    let cursor_icon = platform_output.cursor_icon;
+    let tauri_cursor = egui_cursor_to_tauri_cursor(cursor_icon);
+
+    if let Err(e) = proxy.send_event(Message::Window(
+        window_id,
+        WindowMessage::SetCursorIcon(tauri_cursor),
+    )) {
+        eprintln!("Failed to send cursor message: {}", e);
+    }
+
+    // Handle commands (clipboard, URL opening, etc.)
+    for command in &platform_output.commands {
+        match command {
+            egui::output::OutputCommand::CopyText(text) => {
+                clipboard.set_text(text);
+            }
+            egui::output::OutputCommand::CopyImage(image) => {
+                clipboard.set_image(image);
+            }
+            egui::output::OutputCommand::OpenUrl(url) => {
+                url_opener.open(label, url);
+            }
+        }
+    }
+}
+
+/// Carries out a viewport command on the window, or as input for its next frame.
+/// Returns true if input was queued, which needs a repaint to be seen.
+fn handle_viewport_command(
+    label: &str,
+    command: egui::ViewportCommand,
+    input: &mut EguiInput,
+    window_id: tauri_runtime::window::WindowId,
+    proxy: &EventLoopProxy<Message<impl UserEvent>>,
+    clipboard: &mut dyn Clipboard,
+) -> bool {
+    let event = match command {
+        egui::ViewportCommand::RequestCut => egui::Event::Cut,
+        egui::ViewportCommand::RequestCopy => egui::Event::Copy,
+        egui::ViewportCommand::RequestPaste => match clipboard::paste_event(clipboard) {
+            Some(event) => event,
+            None => return false,
+        },
+        // The IME follows `PlatformOutput::ime`, see `EguiWindow::handle_platform_output`
+        egui::ViewportCommand::IMEAllowed(_)
+        | egui::ViewportCommand::IMERect(_)
+        | egui::ViewportCommand::IMEPurpose(_) => return false,
+        command => {
+            match viewport::window_messages(command, input.pixels_per_point()) {
+                Ok(messages) => {
+                    for message in messages {
+                        if let Err(e) = proxy.send_event(Message::Window(window_id, message)) {
+                            eprintln!("Failed to send window message: {}", e);
+                        }
                     }
                 }
-                '0'..='9' => match ch {
-                    '0' => Some(egui::Key::Num0),
-                    '1' => Some(egui::Key::Num1),
-                    '2' => Some(egui::Key::Num2),
-                    '3' => Some(egui::Key::Num3),
-                    '4' => Some(egui::Key::Num4),
-                    '5' => Some(egui::Key::Num5),
-                    '6' => Some(egui::Key::Num6),
-                    '7' => Some(egui::Key::Num7),
-                    '8' => Some(egui::Key::Num8),
-                    '9' => Some(egui::Key::Num9),
-                    _ => None,
-                },
-                ' ' => Some(egui::Key::Space),
-                '\t' => Some(egui::Key::Tab),
-                '\n' | '\r' => Some(egui::Key::Enter),
-                '\x08' => Some(egui::Key::Backspace),
-                '\x7f' => Some(egui::Key::Delete),
-                '\x1b' => Some(egui::Key::Escape),
-                _ => None,
-            }
-        }
-        _ => None,
-    }
-}
-
-fn translate_physical_key(key: &KeyCode) -> Option<egui::Key> {
-    match key {
-        KeyCode::ArrowDown => Some(egui::Key::ArrowDown),
-        KeyCode::ArrowLeft => Some(egui::Key::ArrowLeft),
-        KeyCode::ArrowRight => Some(egui::Key::ArrowRight),
-        KeyCode::ArrowUp => Some(egui::Key::ArrowUp),
-        KeyCode::Escape => Some(egui::Key::Escape),
-        KeyCode::Tab => Some(egui::Key::Tab),
-        KeyCode::Backspace => Some(egui::Key::Backspace),
-        KeyCode::Delete => Some(egui::Key::Delete),
-        KeyCode::Enter => Some(egui::Key::Enter),
-        KeyCode::Space => Some(egui::Key::Space),
-        KeyCode::Insert => Some(egui::Key::Insert),
-        KeyCode::Home => Some(egui::Key::Home),
-        KeyCode::End => Some(egui::Key::End),
-        KeyCode::PageUp => Some(egui::Key::PageUp),
-        KeyCode::PageDown => Some(egui::Key::PageDown),
-        KeyCode::F1 => Some(egui::Key::F1),
-        KeyCode::F2 => Some(egui::Key::F2),
-        KeyCode::F3 => Some(egui::Key::F3),
-        KeyCode::F4 => Some(egui::Key::F4),
-        KeyCode::F5 => Some(egui::Key::F5),
-        KeyCode::F6 => Some(egui::Key::F6),
-        KeyCode::F7 => Some(egui::Key::F7),
-        KeyCode::F8 => Some(egui::Key::F8),
-        KeyCode::F9 => Some(egui::Key::F9),
-        KeyCode::F10 => Some(egui::Key::F10),
-        KeyCode::F11 => Some(egui::Key::F11),
-        KeyCode::F12 => Some(egui::Key::F12),
-        _ => None,
+                Err(reason) => eprintln!("Ignoring viewport command for {}: {}", label, reason),
+            }
+            return false;
+        }
+    };
+
+    input.push_event(event);
+    true
+}
+
+/// The egui window showing the child viewport in the Tauri window `label`, if any.
+fn find_viewport<'a>(
+    windows: &'a mut HashMap<String, EguiWindow>,
+    label: &str,
+) -> Option<(&'a mut EguiWindow, egui::ViewportId)> {
+    windows.values_mut().find_map(|egui_win| {
+        let viewport_id = egui_win
+            .viewports
+            .borrow()
+            .iter()
+            .find(|(_, viewport)| viewport.label == label)
+            .map(|(viewport_id, _)| *viewport_id)?;
+        Some((egui_win, viewport_id))
+    })
+}
+
+fn request_redraw<T: UserEvent>(
+    label: &str,
+    proxy: &EventLoopProxy<Message<T>>,
+    context: &EventLoopIterationContext<'_, T>,
+) {
+    if let Some(id) = get_id_from_label(label, context) {
+        proxy
+            .send_event(Message::Window(id, WindowMessage::RequestRedraw))
+            .ok();
+    }
+}
+
+/// Opens a Tauri window for a child viewport of the egui window `parent_label`,
+/// rendered with the same options.
+fn open_viewport(
+    app: &AppHandle,
+    parent_label: &str,
+    viewport_id: egui::ViewportId,
+    parent: egui::ViewportId,
+    builder: egui::ViewportBuilder,
+    zoom_factor: f32,
+    native_textures: &NativeTextures,
+) -> Result<ViewportWindow, Error> {
+    let label = format!("{}-viewport-{:x}", parent_label, viewport_id.0.value());
+    let window = viewport::window_builder(app, &label, &builder, zoom_factor)?.build()?;
+
+    let (renderer, mut input) = match create_renderer(app, parent_label, &window) {
+        Ok(created) => created,
+        Err(e) => {
+            window.destroy().ok();
+            return Err(e);
+        }
+    };
+    input.viewport_id = viewport_id;
+    input.zoom_factor = zoom_factor;
+    native_textures.add_renderer(&renderer.egui_renderer());
+
+    Ok(ViewportWindow::new(
+        window, renderer, input, parent, builder,
+    ))
+}
+
+/// Drops the viewport's surface before destroying the window it draws into.
+fn close_viewport(viewport: ViewportWindow) {
+    let window = viewport.window.clone();
+    drop(viewport);
+    if let Err(e) = window.destroy() {
+        eprintln!(
+            "Failed to close the window of viewport {}: {}",
+            window.label(),
+            e
+        );
+    }
+}
+
+/// Runs an immediate viewport in its own window, opening it on first use.
+fn show_immediate_viewport(
+    app: &AppHandle,
+    parent_label: &str,
+    viewports: &ViewportMap,
+    native_textures: &NativeTextures,
+    context: &egui::Context,
+    immediate: egui::ImmediateViewport<'_>,
+) {
+    let egui::ImmediateViewport {
+        ids,
+        builder,
+        mut viewport_ui_cb,
+    } = immediate;
+
+    // Taken out of the map while running, as it may show immediate viewports itself
+    let existing = viewports.borrow_mut().remove(&ids.this);
+    let viewport = match existing {
+        Some(viewport) => Ok(viewport),
+        None => open_viewport(
+            app,
+            parent_label,
+            ids.this,
+            ids.parent,
+            builder,
+            context.zoom_factor(),
+            native_textures,
+        ),
+    };
+
+    match viewport {
+        Ok(mut viewport) => {
+            viewport.run(context, |ctx| viewport_ui_cb(ctx));
+            viewports.borrow_mut().insert(ids.this, viewport);
+        }
+        Err(e) => {
+            eprintln!("Failed to open a window for viewport {:?}: {}", ids.this, e);
+            // egui expects the UI to run either way, so show it in the parent
+            viewport_ui_cb(context);
+        }
     }
 }
 
@@ -502,12 +1483,68 @@ fn egui_cursor_to_tauri_cursor(egui_cursor: egui::CursorIcon) -> CursorIcon {
     }
 }
 
+/// Starts and stops egui in Tauri windows.
+///
+/// Apps and renderers stay on the event loop thread, so these must be called on
+/// the main thread (e.g. in `setup` or through `AppHandle::run_on_main_thread`).
 pub trait AppHandleExt {
+    /// Starts rendering the UI `ui_fn` describes in the window with the given label,
+    /// see [`Self::start_egui_app_for_window`].
     fn start_egui_for_window(
         &self,
         label: &str,
         ui_fn: Box<dyn FnMut(&egui::Context)>,
-    ) -> Result<(), Error>;
+    ) -> Result<EguiHandle, Error>;
+
+    /// Starts rendering an [`EguiApp`] in the window with the given label.
+    /// Fails if egui already runs in it, see [`Self::stop_egui_for_window`].
+    ///
+    /// Viewports the app shows with `ctx.show_viewport_deferred` or
+    /// `ctx.show_viewport_immediate` open in new windows, labelled
+    /// `<label>-viewport-<id>`, and close with the window or once the app stops showing them.
+    fn start_egui_app_for_window(
+        &self,
+        label: &str,
+        app: impl EguiApp + 'static,
+    ) -> Result<EguiHandle, Error>;
+
+    /// Starts rendering an [`EguiApp`] into a part of the window with the given label,
+    /// e.g. next to a webview. Pointer events outside of the region are left to the webview.
+    fn start_egui_for_window_in_region(
+        &self,
+        label: &str,
+        region: EguiRegion,
+        app: impl EguiApp + 'static,
+    ) -> Result<EguiHandle, Error>;
+
+    /// Starts rendering an [`EguiApp`] as an overlay of the webview in the window
+    /// with the given label, see [`OverlayLayer`].
+    ///
+    /// Pointer and keyboard events are only taken from the webview while egui wants them,
+    /// e.g. while hovering an `egui::Window` or typing into a text field. Paint the UI
+    /// into areas (windows, panels with a transparent frame) rather than a `CentralPanel`,
+    /// which covers the whole webview.
+    ///
+    /// The returned handle has the label of the window egui is drawn in, which is
+    /// also the one to pass to [`Self::stop_egui_for_window`].
+    fn start_egui_overlay(
+        &self,
+        label: &str,
+        layer: OverlayLayer,
+        app: impl EguiApp + 'static,
+    ) -> Result<EguiHandle, Error>;
+
+    /// Stops rendering egui in the window and releases its GPU resources.
+    /// Returns `None` if egui wasn't running for this window.
+    fn stop_egui_for_window(&self, label: &str) -> Result<Option<EguiWindowState>, Error>;
+
+    /// The wgpu device shared by all egui windows.
+    /// `None` until egui was started for the first window.
+    fn egui_gpu_context(&self) -> Option<GpuContext>;
+
+    /// Renders the egui window's last frame into an image again, without touching the window
+    /// itself or running the app.
+    fn capture_egui_window(&self, label: &str) -> Result<egui::ColorImage, Error>;
 }
 
 impl AppHandleExt for AppHandle {
@@ -515,47 +1552,224 @@ impl AppHandleExt for AppHandle {
         &self,
         label: &str,
         ui_fn: Box<dyn FnMut(&egui::Context)>,
-    ) -> Result<(), Error> {
-        // check if plugin is init'd
-        let egui_windows = self
-            .try_state::<EguiWindowMap>()
-            .ok_or(Error::msg("TauriPluginEgui is not initialized"))?;
+    ) -> Result<EguiHandle, Error> {
+        self.start_egui_app_for_window(label, ui_fn)
+    }
+
+    fn start_egui_app_for_window(
+        &self,
+        label: &str,
+        app: impl EguiApp + 'static,
+    ) -> Result<EguiHandle, Error> {
+        start_egui(self, label, app, None, None)
+    }
 
-        // check if window exists
-        let window = self
+    fn start_egui_for_window_in_region(
+        &self,
+        label: &str,
+        region: EguiRegion,
+        app: impl EguiApp + 'static,
+    ) -> Result<EguiHandle, Error> {
+        start_egui(self, label, app, None, Some(region))
+    }
+
+    fn start_egui_overlay(
+        &self,
+        label: &str,
+        layer: OverlayLayer,
+        app: impl EguiApp + 'static,
+    ) -> Result<EguiHandle, Error> {
+        // Windows are only created on the event loop thread, check before opening one
+        event_loop_windows(self)?;
+        let parent = self
             .get_window(label)
             .ok_or(Error::msg("No Window found with the provided label."))?;
 
-        // extract relevant window details
-        let scale_factor = window.scale_factor().unwrap_or(1.0) as f32;
-        let size = window.inner_size()?;
-        let PhysicalSize { width, height } = size;
-
-        // create egui context + renderer
-        let context = egui::Context::default();
-        context.set_zoom_factor(scale_factor);
-        let renderer =
-            tauri::async_runtime::block_on(
-                async move { Renderer::new(window, width, height).await },
-            )?;
-
-        // track in the plugin state
-        let mut managed_windows = egui_windows.lock().unwrap();
-        managed_windows.insert(
-            label.to_string(),
-            EguiWindow {
-                context,
-                renderer,
-                ui_fn,
-                size,
-                start_time: Instant::now(),
-                egui_input: egui::RawInput::default(),
-                pointer_pos: None,
-                scale_factor,
-                modifiers: egui::Modifiers::NONE,
-            },
-        );
+        match layer {
+            OverlayLayer::Below => {
+                start_egui(self, label, app, Some(Overlay::new(layer, parent)), None)
+            }
+            OverlayLayer::Above => {
+                let window = overlay::overlay_window(self, &parent)?;
+                let overlay = Overlay::new(layer, parent);
+                overlay.follow_parent(&window);
 
-        Ok(())
+                let started = start_egui(self, window.label(), app, Some(overlay), None);
+                if started.is_err() {
+                    window.destroy().ok();
+                }
+                started
+            }
+        }
+    }
+
+    fn stop_egui_for_window(&self, label: &str) -> Result<Option<EguiWindowState>, Error> {
+        let egui_windows = event_loop_windows(self)?;
+
+        let storage = self
+            .try_state::<EguiStorage>()
+            .ok_or(Error::msg("TauriPluginEgui is not initialized"))?;
+
+        let removed = borrow_windows(&egui_windows)?.remove(label);
+        Ok(removed.map(|egui_win| stop_egui_window(egui_win, &mut storage.lock().unwrap())))
+    }
+
+    fn egui_gpu_context(&self) -> Option<GpuContext> {
+        self.try_state::<SharedGpu>()?.get()
+    }
+
+    fn capture_egui_window(&self, label: &str) -> Result<egui::ColorImage, Error> {
+        let egui_windows = event_loop_windows(self)?;
+
+        let mut windows = borrow_windows(&egui_windows)?;
+        let egui_win = windows
+            .get_mut(label)
+            .ok_or(Error::msg(format!("There's no egui window {}", label)))?;
+        egui_win.capture()
     }
 }
+
+/// Starts rendering `egui_app` in the window with the given label.
+fn start_egui(
+    app: &AppHandle,
+    label: &str,
+    egui_app: impl EguiApp + 'static,
+    overlay: Option<Overlay>,
+    region: Option<EguiRegion>,
+) -> Result<EguiHandle, Error> {
+    // check if plugin is init'd
+    let egui_windows = event_loop_windows(app)?;
+    let tasks = app
+        .try_state::<TaskSender>()
+        .ok_or(Error::msg("TauriPluginEgui is not initialized"))?
+        .inner()
+        .clone();
+
+    // check if window exists
+    let window = app
+        .get_window(label)
+        .ok_or(Error::msg("No Window found with the provided label."))?;
+
+    // Replacing a running app would skip its teardown and orphan its viewports
+    if borrow_windows(&egui_windows)?.contains_key(label) {
+        return Err(Error::msg(format!(
+            "egui is already running in {}, stop it with `stop_egui_for_window` first.",
+            label
+        )));
+    }
+
+    // create egui context + renderer
+    let context = egui::Context::default();
+    // Child viewports get windows of their own instead of being embedded
+    context.set_embed_viewports(false);
+
+    // egui may ask for a repaint from any thread, record it and wake up the
+    // event loop so the plugin picks up the new schedule
+    let repaint = RepaintSchedule::default();
+    let schedule = repaint.clone();
+    let wake_up = tasks.clone();
+    context.set_request_repaint_callback(move |info| {
+        if schedule.request_after(info.viewport_id, info.delay) {
+            wake_up.send(|| {});
+        }
+    });
+    let (renderer, mut input) = create_renderer(app, label, &window)?;
+    input.region = region
+        .as_ref()
+        .map(|region| region.bounds(input.size, input.native_pixels_per_point as f64));
+
+    // track in the plugin state
+    let frame = Frame::new(window.clone(), app.clone());
+    let gpu = app
+        .egui_gpu_context()
+        .ok_or(Error::msg("TauriPluginEgui is not initialized"))?;
+    let native_textures = NativeTextures::new(gpu.device.clone());
+    native_textures.add_renderer(&renderer.egui_renderer());
+    let handle = EguiHandle::new(
+        label.to_string(),
+        context.clone(),
+        tasks,
+        gpu,
+        renderer.target_format(),
+    );
+
+    // Nothing is drawn until the window has a size, see `Gpu::new_async`
+    let minimized = input.size.width == 0 || input.size.height == 0;
+
+    let mut managed_windows = borrow_windows(&egui_windows)?;
+    managed_windows.insert(
+        label.to_string(),
+        EguiWindow {
+            label: label.to_string(),
+            window,
+            context,
+            renderer,
+            app: Box::new(egui_app),
+            frame,
+            input,
+            repaint,
+            ime_allowed: false,
+            ime_cursor_area: None,
+            surface_errors: 0,
+            minimized,
+            surface_released: false,
+            pending_screenshots: Vec::new(),
+            last_frame: None,
+            viewports: ViewportMap::default(),
+            native_textures,
+            overlay,
+            region,
+        },
+    );
+
+    Ok(handle)
+}
+
+/// A renderer for the window, with the options for `options_label`, and its input state.
+fn create_renderer(
+    app: &AppHandle,
+    options_label: &str,
+    window: &tauri::Window,
+) -> Result<(Renderer, EguiInput), Error> {
+    // extract relevant window details
+    let scale_factor = window.scale_factor().unwrap_or(1.0) as f32;
+    let size = window.inner_size()?;
+    let PhysicalSize { width, height } = size;
+
+    let shared_gpu = app
+        .try_state::<SharedGpu>()
+        .ok_or(Error::msg("TauriPluginEgui is not initialized"))?;
+    let renderer_options = app
+        .try_state::<RendererOptions>()
+        .ok_or(Error::msg("TauriPluginEgui is not initialized"))?;
+    let options = renderer_options.for_window(options_label);
+    let surface_window = window.clone();
+    let renderer = tauri::async_runtime::block_on(async move {
+        Renderer::new(surface_window, width, height, &shared_gpu, options).await
+    })?;
+
+    Ok((renderer, EguiInput::new(size, scale_factor)))
+}
+
+/// The egui windows, if the plugin is initialized and we're on the event loop thread.
+fn event_loop_windows(app: &AppHandle) -> Result<EguiWindowMap, Error> {
+    let event_loop_thread = app
+        .try_state::<EventLoopThread>()
+        .ok_or(Error::msg("TauriPluginEgui is not initialized"))?;
+
+    if !event_loop_thread.is_current() {
+        return Err(Error::msg(
+            "egui windows can only be accessed on the main thread.",
+        ));
+    }
+
+    Ok(egui_windows())
+}
+
+fn borrow_windows(
+    egui_windows: &EguiWindowMap,
+) -> Result<std::cell::RefMut<'_, HashMap<String, EguiWindow>>, Error> {
+    egui_windows.try_borrow_mut().map_err(|_| {
+        Error::msg("egui windows can't be changed while rendering, use `Frame::queue_command`.")
+    })
+}
@@ -0,0 +1,204 @@
+use tauri::{LogicalPosition, LogicalSize, PhysicalPosition, PhysicalSize};
+
+/// A rectangle of a window, in physical pixels from its top left corner.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PhysicalRect {
+    pub position: PhysicalPosition<u32>,
+    pub size: PhysicalSize<u32>,
+}
+
+impl PhysicalRect {
+    pub fn new(x: u32, y: u32, width: u32, height: u32) -> Self {
+        Self {
+            position: PhysicalPosition::new(x, y),
+            size: PhysicalSize::new(width, height),
+        }
+    }
+
+    /// The part of the rect inside a window of the given size.
+    fn clamp_to(self, window_size: PhysicalSize<u32>) -> Self {
+        let x = self.position.x.min(window_size.width);
+        let y = self.position.y.min(window_size.height);
+        Self::new(
+            x,
+            y,
+            self.size.width.min(window_size.width - x),
+            self.size.height.min(window_size.height - y),
+        )
+    }
+}
+
+/// A rectangle of a window, in logical pixels from its top left corner.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LogicalRect {
+    pub position: LogicalPosition<f64>,
+    pub size: LogicalSize<f64>,
+}
+
+impl LogicalRect {
+    pub fn new(x: f64, y: f64, width: f64, height: f64) -> Self {
+        Self {
+            position: LogicalPosition::new(x, y),
+            size: LogicalSize::new(width, height),
+        }
+    }
+
+    /// The rect in physical pixels, rounding its edges so neighbouring rects don't leave gaps.
+    fn to_physical(self, scale_factor: f64) -> PhysicalRect {
+        let round = |logical: f64| (logical * scale_factor).round().max(0.0) as u32;
+        let left = round(self.position.x);
+        let top = round(self.position.y);
+        let right = round(self.position.x + self.size.width);
+        let bottom = round(self.position.y + self.size.height);
+        PhysicalRect::new(
+            left,
+            top,
+            right.saturating_sub(left),
+            bottom.saturating_sub(top),
+        )
+    }
+}
+
+/// The part of a Tauri window egui paints into and takes pointer input from,
+/// e.g. to leave room for a webview next to it. Pass it to
+/// [`crate::AppHandleExt::start_egui_for_window_in_region`].
+///
+/// Regions are in physical pixels, the `*_logical` constructors take logical pixels
+/// instead, like webview layouts, so the region keeps its place on monitors with
+/// a different scale factor.
+/// egui sees the region as its whole screen, with `(0, 0)` at the region's top left corner.
+/// The rest of the window is left transparent.
+pub struct EguiRegion {
+    bounds: Box<dyn Fn(PhysicalSize<u32>, f64) -> PhysicalRect>,
+}
+
+impl EguiRegion {
+    /// Always the same rect, whatever the window's size.
+    pub fn fixed(rect: PhysicalRect) -> Self {
+        Self::from_fn(move |_| rect)
+    }
+
+    /// Keeps the given distances to the window's edges as it's resized.
+    pub fn anchored(left: u32, top: u32, right: u32, bottom: u32) -> Self {
+        Self::from_fn(move |size| {
+            PhysicalRect::new(
+                left,
+                top,
+                size.width.saturating_sub(left + right),
+                size.height.saturating_sub(top + bottom),
+            )
+        })
+    }
+
+    /// Computes the rect from the window's inner size each time it changes.
+    pub fn from_fn(bounds: impl Fn(PhysicalSize<u32>) -> PhysicalRect + 'static) -> Self {
+        Self {
+            bounds: Box::new(move |size, _| bounds(size)),
+        }
+    }
+
+    /// Like [`Self::fixed`], in logical pixels.
+    pub fn fixed_logical(rect: LogicalRect) -> Self {
+        Self::from_logical_fn(move |_| rect)
+    }
+
+    /// Like [`Self::anchored`], in logical pixels.
+    pub fn anchored_logical(left: f64, top: f64, right: f64, bottom: f64) -> Self {
+        Self::from_logical_fn(move |size| {
+            LogicalRect::new(
+                left,
+                top,
+                (size.width - left - right).max(0.0),
+                (size.height - top - bottom).max(0.0),
+            )
+        })
+    }
+
+    /// Like [`Self::from_fn`], with the window's logical inner size.
+    pub fn from_logical_fn(bounds: impl Fn(LogicalSize<f64>) -> LogicalRect + 'static) -> Self {
+        Self {
+            bounds: Box::new(move |size, scale_factor| {
+                bounds(size.to_logical(scale_factor)).to_physical(scale_factor)
+            }),
+        }
+    }
+
+    /// The region of a window of the given size, in physical pixels and clamped to the window.
+    pub(crate) fn bounds(&self, window_size: PhysicalSize<u32>, scale_factor: f64) -> PhysicalRect {
+        (self.bounds)(window_size, scale_factor).clamp_to(window_size)
+    }
+}
+
+/// Moves paint jobs laid out at the origin to where the region is, in points.
+pub(crate) fn translate_paint_jobs(
+    paint_jobs: &mut [egui::epaint::ClippedPrimitive],
+    offset: egui::Vec2,
+) {
+    if offset == egui::Vec2::ZERO {
+        return;
+    }
+
+    for job in paint_jobs {
+        job.clip_rect = job.clip_rect.translate(offset);
+        match &mut job.primitive {
+            egui::epaint::Primitive::Mesh(mesh) => mesh.translate(offset),
+            egui::epaint::Primitive::Callback(callback) => {
+                callback.rect = callback.rect.translate(offset);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn anchored_regions_follow_the_window_size() {
+        let region = EguiRegion::anchored(200, 0, 0, 40);
+        assert_eq!(
+            region.bounds(PhysicalSize::new(800, 600), 1.0),
+            PhysicalRect::new(200, 0, 600, 560)
+        );
+        assert_eq!(
+            region.bounds(PhysicalSize::new(1000, 700), 1.0),
+            PhysicalRect::new(200, 0, 800, 660)
+        );
+
+        // Windows smaller than the insets leave no room for egui
+        assert_eq!(
+            region.bounds(PhysicalSize::new(100, 20), 1.0),
+            PhysicalRect::new(100, 0, 0, 0)
+        );
+    }
+
+    #[test]
+    fn logical_regions_follow_the_scale_factor() {
+        let physical = EguiRegion::anchored(200, 0, 0, 40);
+        let logical = EguiRegion::anchored_logical(200.0, 0.0, 0.0, 40.0);
+
+        // The same logical window on a monitor with twice the scale
+        let window_size = PhysicalSize::new(1600, 1200);
+        assert_eq!(
+            physical.bounds(window_size, 2.0),
+            PhysicalRect::new(200, 0, 1400, 1160)
+        );
+        assert_eq!(
+            logical.bounds(window_size, 2.0),
+            PhysicalRect::new(400, 0, 1200, 1120)
+        );
+
+        // Rounded edges leave no gap to a neighbour ending where the region starts
+        let rect = LogicalRect::new(10.5, 0.0, 20.25, 10.0).to_physical(1.5);
+        assert_eq!(rect, PhysicalRect::new(16, 0, 30, 15));
+    }
+
+    #[test]
+    fn regions_are_clamped_to_the_window() {
+        let region = EguiRegion::fixed(PhysicalRect::new(600, 100, 400, 400));
+        assert_eq!(
+            region.bounds(PhysicalSize::new(800, 300), 1.0),
+            PhysicalRect::new(600, 100, 200, 200)
+        );
+    }
+}
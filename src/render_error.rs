@@ -0,0 +1,134 @@
+use egui_wgpu::wgpu;
+use tauri::{AppHandle, Emitter};
+
+/// Emitted when an egui window keeps failing to render, with a [`RenderErrorPayload`].
+pub const RENDER_ERROR_EVENT: &str = "egui://render-error";
+
+/// A hook that is told when an egui window keeps failing to render.
+pub type RenderErrorHandler = Box<dyn Fn(&AppHandle, &RenderErrorPayload) + Send>;
+
+/// How many frames in a row may fail before the app is told about it.
+/// Single failures are expected, e.g. while a window is being resized.
+const MAX_CONSECUTIVE_FAILURES: u32 = 3;
+
+/// Why a frame could not be rendered, mirroring `wgpu::SurfaceError`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum RenderErrorKind {
+    /// The GPU took too long to hand out a surface texture.
+    Timeout,
+    /// The surface no longer matches the window, even after reconfiguring it.
+    Outdated,
+    /// The surface was lost, even after reconfiguring it.
+    Lost,
+    /// The GPU ran out of memory.
+    OutOfMemory,
+    Other,
+}
+
+impl From<&wgpu::SurfaceError> for RenderErrorKind {
+    fn from(error: &wgpu::SurfaceError) -> Self {
+        match error {
+            wgpu::SurfaceError::Timeout => Self::Timeout,
+            wgpu::SurfaceError::Outdated => Self::Outdated,
+            wgpu::SurfaceError::Lost => Self::Lost,
+            wgpu::SurfaceError::OutOfMemory => Self::OutOfMemory,
+            wgpu::SurfaceError::Other => Self::Other,
+        }
+    }
+}
+
+/// Payload of the [`RENDER_ERROR_EVENT`].
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RenderErrorPayload {
+    /// Label of the window that failed to render.
+    pub label: String,
+    pub kind: RenderErrorKind,
+    pub message: String,
+    /// How many frames in a row failed, including this one.
+    pub consecutive_failures: u32,
+}
+
+/// Counts failed frames per window and tells the app once failures persist.
+pub(crate) struct RenderErrorReporter {
+    app: AppHandle,
+    handler: Option<RenderErrorHandler>,
+}
+
+impl RenderErrorReporter {
+    pub fn new(app: AppHandle, handler: Option<RenderErrorHandler>) -> Self {
+        Self { app, handler }
+    }
+
+    /// Records a failed frame. `consecutive_failures` counts this one too.
+    pub fn report(&self, label: &str, error: &wgpu::SurfaceError, consecutive_failures: u32) {
+        let kind = RenderErrorKind::from(error);
+        if !should_report(kind, consecutive_failures) {
+            return;
+        }
+
+        eprintln!("Failed to render egui window {}: {}", label, error);
+
+        let payload = RenderErrorPayload {
+            label: label.to_string(),
+            kind,
+            message: error.to_string(),
+            consecutive_failures,
+        };
+        if let Some(handler) = &self.handler {
+            handler(&self.app, &payload);
+        }
+        if let Err(e) = self.app.emit(RENDER_ERROR_EVENT, payload) {
+            eprintln!("Failed to emit {}: {}", RENDER_ERROR_EVENT, e);
+        }
+    }
+}
+
+/// Whether a failed frame is worth telling the app about, once per run of failures.
+fn should_report(kind: RenderErrorKind, consecutive_failures: u32) -> bool {
+    // Running out of memory won't fix itself, anything else gets a few more tries
+    kind == RenderErrorKind::OutOfMemory || consecutive_failures == MAX_CONSECUTIVE_FAILURES
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Which frames of a run get reported, counting failures like the plugin does.
+    fn reported_frames(frames: &[Option<RenderErrorKind>]) -> Vec<usize> {
+        let mut consecutive_failures = 0;
+        let mut reported = Vec::new();
+        for (i, frame) in frames.iter().enumerate() {
+            match frame {
+                Some(kind) => {
+                    consecutive_failures += 1;
+                    if should_report(*kind, consecutive_failures) {
+                        reported.push(i);
+                    }
+                }
+                None => consecutive_failures = 0,
+            }
+        }
+        reported
+    }
+
+    #[test]
+    fn persistent_failures_are_reported_once_at_the_threshold() {
+        let frames = [Some(RenderErrorKind::Timeout); 10];
+        assert_eq!(reported_frames(&frames), vec![2]);
+    }
+
+    #[test]
+    fn successful_frames_reset_the_count() {
+        let lost = Some(RenderErrorKind::Lost);
+        let frames = [lost, lost, None, lost, lost, None, lost, lost, lost];
+        assert_eq!(reported_frames(&frames), vec![8]);
+    }
+
+    #[test]
+    fn out_of_memory_is_always_reported() {
+        let frames = [Some(RenderErrorKind::OutOfMemory); 4];
+        assert_eq!(reported_frames(&frames), vec![0, 1, 2, 3]);
+    }
+}
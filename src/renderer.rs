@@ -1,9 +1,53 @@
 use anyhow::Error;
 use egui_wgpu::wgpu;
+use std::sync::{mpsc, Arc, Mutex, RwLock};
+
+/// How egui windows are rendered, see [`crate::Builder::renderer_options`].
+#[derive(Debug, Clone)]
+pub struct EguiRendererOptions {
+    /// How frames are presented, e.g. `AutoVsync`, `Mailbox` or `Immediate`.
+    /// Falls back to `Fifo` (vsync) if the window's surface doesn't support it.
+    pub present_mode: wgpu::PresentMode,
+    pub desired_maximum_frame_latency: u32,
+    /// Samples per pixel for multisample anti-aliasing, 1 disables it.
+    pub msaa_samples: u32,
+    /// Format of a depth buffer (e.g. for paint callbacks), `None` for no depth buffer.
+    pub depth_format: Option<wgpu::TextureFormat>,
+    /// Dither the output to avoid banding in gradients.
+    pub dithering: bool,
+
+    // The options below pick the GPU device that all egui windows share, so
+    // they're only read from `Builder::renderer_options`, never from per-window options.
+    pub power_preference: wgpu::PowerPreference,
+    pub backends: wgpu::Backends,
+    pub required_features: wgpu::Features,
+    /// Defaults to wgpu's default limits, with texture sizes raised to what the adapter supports.
+    pub required_limits: Option<wgpu::Limits>,
+}
+
+impl Default for EguiRendererOptions {
+    fn default() -> Self {
+        Self {
+            present_mode: wgpu::PresentMode::AutoVsync,
+            desired_maximum_frame_latency: 2,
+            msaa_samples: 1,
+            depth_format: None,
+            dithering: false,
+            power_preference: wgpu::PowerPreference::default(),
+            backends: wgpu::Backends::all(),
+            required_features: wgpu::Features::default(),
+            required_limits: None,
+        }
+    }
+}
 
 pub struct Renderer {
     gpu: Gpu,
-    egui_renderer: egui_wgpu::Renderer,
+    /// Shared with [`crate::EguiHandle`] so native textures can be registered from any thread
+    egui_renderer: Arc<RwLock<egui_wgpu::Renderer>>,
+    /// Read the next rendered frame back, see [`Self::request_screenshot`]
+    screenshot_requested: bool,
+    screenshot: Option<Result<egui::ColorImage, Error>>,
 }
 
 impl Renderer {
@@ -11,34 +55,128 @@ impl Renderer {
         window: impl Into<wgpu::SurfaceTarget<'static>>,
         width: u32,
         height: u32,
+        shared_gpu: &SharedGpu,
+        options: &EguiRendererOptions,
     ) -> Result<Self, Error> {
-        let gpu = Gpu::new_async(window, width, height).await?;
+        let gpu = Gpu::new_async(window, width, height, shared_gpu, options).await?;
 
-        let egui_renderer =
-            egui_wgpu::Renderer::new(&gpu.device, gpu.surface_config.format, None, 1, false);
+        let egui_renderer = egui_wgpu::Renderer::new(
+            &gpu.device,
+            gpu.surface_config.format,
+            gpu.depth_format,
+            gpu.msaa_samples,
+            options.dithering,
+        );
 
-        Ok(Self { gpu, egui_renderer })
+        Ok(Self {
+            gpu,
+            egui_renderer: Arc::new(RwLock::new(egui_renderer)),
+            screenshot_requested: false,
+            screenshot: None,
+        })
+    }
+
+    /// A renderer drawing into a texture instead of a window, see [`Self::capture_frame`].
+    pub fn new_offscreen(
+        gpu_context: &GpuContext,
+        width: u32,
+        height: u32,
+        options: &EguiRendererOptions,
+    ) -> Self {
+        let gpu = Gpu::new_offscreen(gpu_context, width, height, options);
+
+        let egui_renderer = egui_wgpu::Renderer::new(
+            &gpu.device,
+            gpu.surface_config.format,
+            gpu.depth_format,
+            gpu.msaa_samples,
+            options.dithering,
+        );
+
+        Self {
+            gpu,
+            egui_renderer: Arc::new(RwLock::new(egui_renderer)),
+            screenshot_requested: false,
+            screenshot: None,
+        }
     }
 
     pub fn resize(&mut self, width: u32, height: u32) {
         self.gpu.resize(width, height);
     }
 
+    /// Drops the window's surface, e.g. while the window may be closing,
+    /// drawing offscreen until [`Self::attach_surface`].
+    pub fn detach_surface(&mut self) {
+        if let RenderTarget::Surface(_) = self.gpu.target {
+            self.gpu.target = RenderTarget::Texture(self.gpu.create_target_texture());
+        }
+    }
+
+    /// Draws into `window` again after [`Self::detach_surface`].
+    pub async fn attach_surface(
+        &mut self,
+        window: impl Into<wgpu::SurfaceTarget<'static>>,
+        shared_gpu: &SharedGpu,
+    ) -> Result<(), Error> {
+        let (_, surface) = shared_gpu.create_surface(window).await?;
+        surface.configure(&self.gpu.device, &self.gpu.surface_config);
+        self.gpu.target = RenderTarget::Surface(surface);
+        Ok(())
+    }
+
+    /// The egui renderer, e.g. to register native textures.
+    pub fn egui_renderer(&self) -> Arc<RwLock<egui_wgpu::Renderer>> {
+        self.egui_renderer.clone()
+    }
+
+    /// What frames are drawn into, for pipelines used in paint callbacks.
+    pub fn target_format(&self) -> TargetFormat {
+        TargetFormat {
+            format: self.gpu.surface_config.format,
+            msaa_samples: self.gpu.msaa_samples,
+            depth_format: self.gpu.depth_format,
+        }
+    }
+
+    /// Drops a frame that can't be shown (e.g. while the window has no size),
+    /// keeping its texture changes for later frames.
+    pub fn skip_frame(&mut self, textures_delta: egui::TexturesDelta) {
+        let mut egui_renderer = self.egui_renderer.write().unwrap();
+        update_textures(&mut egui_renderer, &self.gpu, &textures_delta);
+        free_textures(&mut egui_renderer, &textures_delta);
+    }
+
+    /// Reads the next frame back once it's rendered, see [`Self::take_screenshot`].
+    pub fn request_screenshot(&mut self) {
+        self.screenshot_requested = true;
+    }
+
+    /// The frame requested with [`Self::request_screenshot`], if it was rendered since.
+    pub fn take_screenshot(&mut self) -> Option<Result<egui::ColorImage, Error>> {
+        self.screenshot.take()
+    }
+
     pub fn render_frame(
         &mut self,
         screen_descriptor: egui_wgpu::ScreenDescriptor,
-        paint_jobs: Vec<egui::epaint::ClippedPrimitive>,
+        paint_jobs: &[egui::epaint::ClippedPrimitive],
         textures_delta: egui::TexturesDelta,
-        // _delta_time: std::time::Duration,
-    ) {
-        for (id, image_delta) in &textures_delta.set {
-            self.egui_renderer
-                .update_texture(&self.gpu.device, &self.gpu.queue, *id, image_delta);
-        }
+        clear_color: wgpu::Color,
+    ) -> Result<(), wgpu::SurfaceError> {
+        let egui_renderer = self.egui_renderer.clone();
+        let mut egui_renderer = egui_renderer.write().unwrap();
 
-        for id in &textures_delta.free {
-            self.egui_renderer.free_texture(id);
-        }
+        // Textures are only sent once, so upload them even if this frame gets skipped
+        update_textures(&mut egui_renderer, &self.gpu, &textures_delta);
+
+        let target_texture = match self.gpu.current_texture() {
+            Ok(target_texture) => target_texture,
+            Err(e) => {
+                free_textures(&mut egui_renderer, &textures_delta);
+                return Err(e);
+            }
+        };
 
         let mut encoder = self
             .gpu
@@ -47,23 +185,18 @@ impl Renderer {
                 label: Some("Render Encoder"),
             });
 
-        self.egui_renderer.update_buffers(
+        // Runs the `prepare` step of paint callbacks, which may record their own commands
+        let callback_commands = egui_renderer.update_buffers(
             &self.gpu.device,
             &self.gpu.queue,
             &mut encoder,
-            &paint_jobs,
+            paint_jobs,
             &screen_descriptor,
         );
 
-        let surface_texture = self
-            .gpu
-            .surface
-            .get_current_texture()
-            .expect("Failed to get surface texture!");
-
         let surface_texture_view =
-            surface_texture
-                .texture
+            target_texture
+                .texture()
                 .create_view(&wgpu::TextureViewDescriptor {
                     label: wgpu::Label::default(),
                     aspect: wgpu::TextureAspect::default(),
@@ -78,84 +211,477 @@ impl Renderer {
 
         encoder.insert_debug_marker("Render scene");
 
+        // With MSAA we draw into the multisampled texture and resolve into the surface
+        let (view, resolve_target) = match &self.gpu.msaa_view {
+            Some(msaa_view) => (msaa_view, Some(&surface_texture_view)),
+            None => (&surface_texture_view, None),
+        };
+
         // need this block to preserve encoder ownership
         {
             let render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
                 label: Some("Render Pass"),
                 color_attachments: &[Some(wgpu::RenderPassColorAttachment {
-                    view: &surface_texture_view,
-                    resolve_target: None,
+                    view,
+                    resolve_target,
                     ops: wgpu::Operations {
-                        load: wgpu::LoadOp::Clear(wgpu::Color::TRANSPARENT),
+                        load: wgpu::LoadOp::Clear(clear_color),
                         store: wgpu::StoreOp::Store,
                     },
                 })],
-                depth_stencil_attachment: None,
+                depth_stencil_attachment: self.gpu.depth_view.as_ref().map(|view| {
+                    wgpu::RenderPassDepthStencilAttachment {
+                        view,
+                        depth_ops: Some(wgpu::Operations {
+                            load: wgpu::LoadOp::Clear(1.0),
+                            store: wgpu::StoreOp::Discard,
+                        }),
+                        stencil_ops: None,
+                    }
+                }),
                 timestamp_writes: None,
                 occlusion_query_set: None,
             });
 
-            self.egui_renderer.render(
+            egui_renderer.render(
                 &mut render_pass.forget_lifetime(),
-                &paint_jobs,
+                paint_jobs,
                 &screen_descriptor,
             );
         }
 
-        self.gpu.queue.submit(std::iter::once(encoder.finish()));
-        surface_texture.present();
+        self.gpu.queue.submit(
+            callback_commands
+                .into_iter()
+                .chain(std::iter::once(encoder.finish())),
+        );
+
+        // Surface textures can't be read once presented
+        if std::mem::take(&mut self.screenshot_requested) {
+            self.screenshot = Some(self.gpu.read_texture(target_texture.texture()));
+        }
+        target_texture.present();
+
+        free_textures(&mut egui_renderer, &textures_delta);
+        Ok(())
+    }
+
+    /// Renders a frame into a texture through [`Self::render_frame`] and reads it back.
+    /// Window renderers draw into a temporary texture, leaving the window as is.
+    pub fn capture_frame(
+        &mut self,
+        screen_descriptor: egui_wgpu::ScreenDescriptor,
+        paint_jobs: &[egui::epaint::ClippedPrimitive],
+        textures_delta: egui::TexturesDelta,
+        clear_color: wgpu::Color,
+    ) -> Result<egui::ColorImage, Error> {
+        let texture = match &self.gpu.target {
+            RenderTarget::Texture(texture) => texture.clone(),
+            RenderTarget::Surface(_) => self.gpu.create_target_texture(),
+        };
+
+        // Keep a screenshot requested for the window for its next real frame
+        let screenshot_requested = std::mem::replace(&mut self.screenshot_requested, true);
+        let target = std::mem::replace(&mut self.gpu.target, RenderTarget::Texture(texture));
+        let rendered =
+            self.render_frame(screen_descriptor, paint_jobs, textures_delta, clear_color);
+        self.gpu.target = target;
+        self.screenshot_requested = screenshot_requested;
+        rendered?;
+
+        self.take_screenshot()
+            .unwrap_or(Err(Error::msg("The captured frame wasn't read back.")))
     }
 }
 
-#[derive(Debug)]
-pub struct Gpu {
-    pub surface: wgpu::Surface<'static>,
+fn update_textures(
+    egui_renderer: &mut egui_wgpu::Renderer,
+    gpu: &Gpu,
+    textures_delta: &egui::TexturesDelta,
+) {
+    for (id, image_delta) in &textures_delta.set {
+        egui_renderer.update_texture(&gpu.device, &gpu.queue, *id, image_delta);
+    }
+}
+
+fn free_textures(egui_renderer: &mut egui_wgpu::Renderer, textures_delta: &egui::TexturesDelta) {
+    for id in &textures_delta.free {
+        egui_renderer.free_texture(id);
+    }
+}
+
+/// The attachments of an egui window's render pass.
+///
+/// Render pipelines drawn in [`egui_wgpu::Callback`]s have to match them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TargetFormat {
+    pub format: wgpu::TextureFormat,
+    /// Pass it as `MultisampleState::count`
+    pub msaa_samples: u32,
+    /// Egui clears it to 1.0 each frame, see [`EguiRendererOptions::depth_format`]
+    pub depth_format: Option<wgpu::TextureFormat>,
+}
+
+/// The wgpu device shared by every egui window, so GPU resources
+/// (e.g. textures) created once can be used in all of them.
+///
+/// All handles are cheap to clone.
+#[derive(Debug, Clone)]
+pub struct GpuContext {
+    pub instance: wgpu::Instance,
+    pub adapter: wgpu::Adapter,
     pub device: wgpu::Device,
     pub queue: wgpu::Queue,
-    pub surface_config: wgpu::SurfaceConfiguration,
-    pub surface_format: wgpu::TextureFormat,
 }
 
-impl Gpu {
-    // TODO: what is this used for?
-    // pub fn aspect_ratio(&self) -> f32 {
-    //     self.surface_config.width as f32 / self.surface_config.height.max(1) as f32
-    // }
+impl GpuContext {
+    /// A context without any window, e.g. for rendering in CI. Falls back to a
+    /// software adapter if there's no GPU.
+    pub async fn new_headless(options: &EguiRendererOptions) -> Result<Self, Error> {
+        let instance = wgpu::Instance::new(&wgpu::InstanceDescriptor {
+            backends: options.backends,
+            ..Default::default()
+        });
 
-    pub fn resize(&mut self, width: u32, height: u32) {
-        self.surface_config.width = width;
-        self.surface_config.height = height;
-        self.surface.configure(&self.device, &self.surface_config);
+        let mut adapter_options = wgpu::RequestAdapterOptions {
+            power_preference: options.power_preference,
+            compatible_surface: None,
+            force_fallback_adapter: false,
+        };
+        let adapter = match instance.request_adapter(&adapter_options).await {
+            Ok(adapter) => adapter,
+            Err(_) => {
+                adapter_options.force_fallback_adapter = true;
+                instance.request_adapter(&adapter_options).await?
+            }
+        };
+
+        Self::new_with_adapter(instance, adapter, options).await
     }
 
-    pub async fn new_async(
-        window: impl Into<wgpu::SurfaceTarget<'static>>,
-        width: u32,
-        height: u32,
+    async fn new_async(
+        instance: wgpu::Instance,
+        compatible_surface: &wgpu::Surface<'_>,
+        options: &EguiRendererOptions,
     ) -> Result<Self, Error> {
-        let instance = wgpu::Instance::default();
-        let surface = instance.create_surface(window)?;
-
         let adapter = instance
             .request_adapter(&wgpu::RequestAdapterOptions {
-                power_preference: wgpu::PowerPreference::default(),
-                compatible_surface: Some(&surface),
+                power_preference: options.power_preference,
+                compatible_surface: Some(compatible_surface),
                 force_fallback_adapter: false,
             })
             .await?;
 
+        Self::new_with_adapter(instance, adapter, options).await
+    }
+
+    async fn new_with_adapter(
+        instance: wgpu::Instance,
+        adapter: wgpu::Adapter,
+        options: &EguiRendererOptions,
+    ) -> Result<Self, Error> {
         let (device, queue) = {
             adapter
                 .request_device(&wgpu::DeviceDescriptor {
                     label: Some("WGPU Device"),
                     trace: wgpu::Trace::default(),
                     memory_hints: wgpu::MemoryHints::default(),
-                    required_features: wgpu::Features::default(),
-                    required_limits: wgpu::Limits::default().using_resolution(adapter.limits()),
+                    required_features: options.required_features,
+                    required_limits: options.required_limits.clone().unwrap_or_else(|| {
+                        wgpu::Limits::default().using_resolution(adapter.limits())
+                    }),
                 })
                 .await?
         };
 
+        Ok(Self {
+            instance,
+            adapter,
+            device,
+            queue,
+        })
+    }
+}
+
+/// Plugin state holding the [`GpuContext`], created along with the first egui window.
+pub(crate) struct SharedGpu {
+    context: Mutex<Option<GpuContext>>,
+    /// Picks the instance, adapter and device
+    options: EguiRendererOptions,
+}
+
+impl SharedGpu {
+    pub fn new(options: EguiRendererOptions) -> Self {
+        Self {
+            context: Mutex::new(None),
+            options,
+        }
+    }
+
+    pub fn get(&self) -> Option<GpuContext> {
+        self.context.lock().unwrap().clone()
+    }
+
+    /// Creates a surface for `window`, along with the shared context if there's none yet.
+    async fn create_surface(
+        &self,
+        window: impl Into<wgpu::SurfaceTarget<'static>>,
+    ) -> Result<(GpuContext, wgpu::Surface<'static>), Error> {
+        if let Some(context) = self.get() {
+            let surface = context.instance.create_surface(window)?;
+            if !context.adapter.is_surface_supported(&surface) {
+                return Err(Error::msg(
+                    "The shared GPU adapter can't render to this window.",
+                ));
+            }
+            return Ok((context, surface));
+        }
+
+        let instance = wgpu::Instance::new(&wgpu::InstanceDescriptor {
+            backends: self.options.backends,
+            ..Default::default()
+        });
+        let surface = instance.create_surface(window)?;
+        let context = GpuContext::new_async(instance, &surface, &self.options).await?;
+        *self.context.lock().unwrap() = Some(context.clone());
+        Ok((context, surface))
+    }
+}
+
+/// What a [`Gpu`] draws into.
+#[derive(Debug)]
+pub enum RenderTarget {
+    Surface(wgpu::Surface<'static>),
+    /// Offscreen rendering, readable with [`Gpu::read_texture`]
+    Texture(wgpu::Texture),
+}
+
+/// The texture a single frame is drawn into.
+pub enum TargetTexture {
+    Surface(wgpu::SurfaceTexture),
+    Texture(wgpu::Texture),
+}
+
+impl TargetTexture {
+    pub fn texture(&self) -> &wgpu::Texture {
+        match self {
+            Self::Surface(surface_texture) => &surface_texture.texture,
+            Self::Texture(texture) => texture,
+        }
+    }
+
+    /// Shows the frame in the window, offscreen frames are done once submitted.
+    pub fn present(self) {
+        if let Self::Surface(surface_texture) = self {
+            surface_texture.present();
+        }
+    }
+}
+
+/// A window's surface (or an offscreen texture), drawn into with the shared device.
+#[derive(Debug)]
+pub struct Gpu {
+    pub target: RenderTarget,
+    pub device: wgpu::Device,
+    pub queue: wgpu::Queue,
+    pub surface_config: wgpu::SurfaceConfiguration,
+    pub surface_format: wgpu::TextureFormat,
+    pub msaa_samples: u32,
+    pub depth_format: Option<wgpu::TextureFormat>,
+    /// Multisampled color target, resolved into the surface texture
+    pub msaa_view: Option<wgpu::TextureView>,
+    pub depth_view: Option<wgpu::TextureView>,
+}
+
+impl Gpu {
+    pub fn resize(&mut self, width: u32, height: u32) {
+        // wgpu rejects zero-sized surfaces (e.g. minimized windows on Windows),
+        // keep the old configuration until the window gets a real size again
+        if width == 0 || height == 0 {
+            return;
+        }
+
+        self.surface_config.width = width;
+        self.surface_config.height = height;
+        match &mut self.target {
+            RenderTarget::Surface(surface) => surface.configure(&self.device, &self.surface_config),
+            RenderTarget::Texture(texture) => {
+                *texture = Self::create_texture(&self.device, &self.surface_config)
+            }
+        }
+        self.create_attachments();
+    }
+
+    /// A texture frames can be rendered into instead of the surface.
+    pub fn create_target_texture(&self) -> wgpu::Texture {
+        Self::create_texture(&self.device, &self.surface_config)
+    }
+
+    fn create_texture(device: &wgpu::Device, config: &wgpu::SurfaceConfiguration) -> wgpu::Texture {
+        device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("egui offscreen texture"),
+            size: wgpu::Extent3d {
+                width: config.width,
+                height: config.height,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: config.format,
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::COPY_SRC,
+            view_formats: &[],
+        })
+    }
+
+    /// Copies a texture rendered by this GPU back to the CPU, blocking until it's done.
+    pub fn read_texture(&self, texture: &wgpu::Texture) -> Result<egui::ColorImage, Error> {
+        let to_rgba = match texture.format() {
+            wgpu::TextureFormat::Rgba8Unorm => [0, 1, 2, 3],
+            wgpu::TextureFormat::Bgra8Unorm => [2, 1, 0, 3],
+            format => {
+                return Err(Error::msg(format!(
+                    "Can't read back textures in the {:?} format.",
+                    format
+                )))
+            }
+        };
+
+        if !texture.usage().contains(wgpu::TextureUsages::COPY_SRC) {
+            return Err(Error::msg(
+                "The window's surface can't be read back on this platform.",
+            ));
+        }
+
+        let size = texture.size();
+        let unpadded_bytes_per_row = size.width * 4;
+        let padded_bytes_per_row =
+            wgpu::util::align_to(unpadded_bytes_per_row, wgpu::COPY_BYTES_PER_ROW_ALIGNMENT);
+
+        let buffer = self.device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("egui readback buffer"),
+            size: (padded_bytes_per_row * size.height) as u64,
+            usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+            mapped_at_creation: false,
+        });
+
+        let mut encoder = self
+            .device
+            .create_command_encoder(&wgpu::CommandEncoderDescriptor {
+                label: Some("Readback Encoder"),
+            });
+        encoder.copy_texture_to_buffer(
+            texture.as_image_copy(),
+            wgpu::TexelCopyBufferInfo {
+                buffer: &buffer,
+                layout: wgpu::TexelCopyBufferLayout {
+                    offset: 0,
+                    bytes_per_row: Some(padded_bytes_per_row),
+                    rows_per_image: None,
+                },
+            },
+            size,
+        );
+        self.queue.submit(std::iter::once(encoder.finish()));
+
+        let (sender, receiver) = mpsc::channel();
+        buffer
+            .slice(..)
+            .map_async(wgpu::MapMode::Read, move |result| {
+                sender.send(result).ok();
+            });
+        self.device.poll(wgpu::PollType::Wait)?;
+        receiver.recv()??;
+
+        let pixels = buffer
+            .slice(..)
+            .get_mapped_range()
+            .chunks(padded_bytes_per_row as usize)
+            .flat_map(|padded_row| {
+                padded_row[..unpadded_bytes_per_row as usize]
+                    .chunks(4)
+                    .map(|color| {
+                        egui::Color32::from_rgba_premultiplied(
+                            color[to_rgba[0]],
+                            color[to_rgba[1]],
+                            color[to_rgba[2]],
+                            color[to_rgba[3]],
+                        )
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .collect();
+        buffer.unmap();
+
+        Ok(egui::ColorImage::new(
+            [size.width as usize, size.height as usize],
+            pixels,
+        ))
+    }
+
+    /// (Re)creates the MSAA and depth textures to match the surface size.
+    fn create_attachments(&mut self) {
+        self.msaa_view = (self.msaa_samples > 1)
+            .then(|| self.create_attachment("egui msaa texture", self.surface_config.format));
+        self.depth_view = self
+            .depth_format
+            .map(|format| self.create_attachment("egui depth texture", format));
+    }
+
+    fn create_attachment(&self, label: &str, format: wgpu::TextureFormat) -> wgpu::TextureView {
+        self.device
+            .create_texture(&wgpu::TextureDescriptor {
+                label: Some(label),
+                size: wgpu::Extent3d {
+                    width: self.surface_config.width,
+                    height: self.surface_config.height,
+                    depth_or_array_layers: 1,
+                },
+                mip_level_count: 1,
+                sample_count: self.msaa_samples,
+                dimension: wgpu::TextureDimension::D2,
+                format,
+                usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
+                view_formats: &[],
+            })
+            .create_view(&wgpu::TextureViewDescriptor::default())
+    }
+
+    /// Gets the next texture to draw into, reconfiguring the surface once
+    /// if it was lost or went out of date (e.g. after a resize or GPU reset).
+    pub fn current_texture(&mut self) -> Result<TargetTexture, wgpu::SurfaceError> {
+        let surface = match &self.target {
+            RenderTarget::Surface(surface) => surface,
+            RenderTarget::Texture(texture) => return Ok(TargetTexture::Texture(texture.clone())),
+        };
+
+        let surface_texture = match surface.get_current_texture() {
+            Err(wgpu::SurfaceError::Lost | wgpu::SurfaceError::Outdated) => {
+                surface.configure(&self.device, &self.surface_config);
+                surface.get_current_texture()
+            }
+            result => result,
+        }?;
+        Ok(TargetTexture::Surface(surface_texture))
+    }
+
+    pub async fn new_async(
+        window: impl Into<wgpu::SurfaceTarget<'static>>,
+        width: u32,
+        height: u32,
+        shared_gpu: &SharedGpu,
+        options: &EguiRendererOptions,
+    ) -> Result<Self, Error> {
+        let (
+            GpuContext {
+                adapter,
+                device,
+                queue,
+                ..
+            },
+            surface,
+        ) = shared_gpu.create_surface(window).await?;
+
         let surface_capabilities = surface.get_capabilities(&adapter);
 
         let surface_format = surface_capabilities
@@ -181,25 +707,107 @@ impl Gpu {
             .find(|m| *m != wgpu::CompositeAlphaMode::Opaque)
             .unwrap_or(surface_capabilities.alpha_modes[0]);
 
+        // `Auto*` modes are always supported, the others fall back to vsync
+        let present_mode = match options.present_mode {
+            wgpu::PresentMode::AutoVsync | wgpu::PresentMode::AutoNoVsync => options.present_mode,
+            mode if surface_capabilities.present_modes.contains(&mode) => mode,
+            mode => {
+                eprintln!("Present mode {:?} is not supported, using Fifo", mode);
+                wgpu::PresentMode::Fifo
+            }
+        };
+
+        let msaa_samples = supported_msaa_samples(&adapter, surface_format, options.msaa_samples);
+
+        // Copying from the surface texture allows screenshots of what's shown
+        let usage = wgpu::TextureUsages::RENDER_ATTACHMENT
+            | (surface_capabilities.usages & wgpu::TextureUsages::COPY_SRC);
+
+        // wgpu panics on zero-sized surfaces, a window created minimized is resized
+        // to its real size once it's shown
         let surface_config = wgpu::SurfaceConfiguration {
-            usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
+            usage,
             format: surface_format,
-            width,
-            height,
-            present_mode: surface_capabilities.present_modes[0],
+            width: width.max(1),
+            height: height.max(1),
+            present_mode,
             alpha_mode,
             view_formats: vec![],
-            desired_maximum_frame_latency: 2,
+            desired_maximum_frame_latency: options.desired_maximum_frame_latency,
         };
 
         surface.configure(&device, &surface_config);
 
-        Ok(Self {
-            surface,
+        let mut gpu = Self {
+            target: RenderTarget::Surface(surface),
             device,
             queue,
             surface_config,
             surface_format,
-        })
+            msaa_samples,
+            depth_format: options.depth_format,
+            msaa_view: None,
+            depth_view: None,
+        };
+        gpu.create_attachments();
+
+        Ok(gpu)
+    }
+
+    /// A GPU drawing into a texture, e.g. for screenshots without a window.
+    pub fn new_offscreen(
+        gpu_context: &GpuContext,
+        width: u32,
+        height: u32,
+        options: &EguiRendererOptions,
+    ) -> Self {
+        // egui wants a non-srgb target, like the surface
+        let format = wgpu::TextureFormat::Rgba8Unorm;
+        let msaa_samples =
+            supported_msaa_samples(&gpu_context.adapter, format, options.msaa_samples);
+
+        let surface_config = wgpu::SurfaceConfiguration {
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::COPY_SRC,
+            format,
+            width: width.max(1),
+            height: height.max(1),
+            present_mode: wgpu::PresentMode::AutoVsync,
+            alpha_mode: wgpu::CompositeAlphaMode::Auto,
+            view_formats: vec![],
+            desired_maximum_frame_latency: options.desired_maximum_frame_latency,
+        };
+        let texture = Self::create_texture(&gpu_context.device, &surface_config);
+
+        let mut gpu = Self {
+            target: RenderTarget::Texture(texture),
+            device: gpu_context.device.clone(),
+            queue: gpu_context.queue.clone(),
+            surface_config,
+            surface_format: format,
+            msaa_samples,
+            depth_format: options.depth_format,
+            msaa_view: None,
+            depth_view: None,
+        };
+        gpu.create_attachments();
+
+        gpu
+    }
+}
+
+fn supported_msaa_samples(
+    adapter: &wgpu::Adapter,
+    format: wgpu::TextureFormat,
+    requested: u32,
+) -> u32 {
+    let format_features = adapter.get_texture_format_features(format);
+    if format_features.flags.sample_count_supported(requested) {
+        requested
+    } else {
+        eprintln!(
+            "{}x MSAA is not supported for {:?}, disabling it",
+            requested, format
+        );
+        1
     }
 }
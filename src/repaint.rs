@@ -0,0 +1,99 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// When the viewports of an egui window want their next frame.
+///
+/// Shared between the plugin and egui's repaint callback, which may be
+/// called from any thread (e.g. `ctx.request_repaint()` in a background task).
+#[derive(Clone, Default)]
+pub(crate) struct RepaintSchedule {
+    next_repaint: Arc<Mutex<HashMap<egui::ViewportId, Instant>>>,
+}
+
+impl RepaintSchedule {
+    /// Schedules a repaint of the viewport `delay` from now, unless one is already due sooner.
+    /// Returns true if the next repaint moved earlier.
+    pub fn request_after(&self, viewport_id: egui::ViewportId, delay: Duration) -> bool {
+        // `Duration::MAX` means egui doesn't need another frame
+        let Some(at) = Instant::now().checked_add(delay) else {
+            return false;
+        };
+
+        let mut next_repaint = self.next_repaint.lock().unwrap();
+        match next_repaint.get(&viewport_id) {
+            Some(next) if *next <= at => false,
+            _ => {
+                next_repaint.insert(viewport_id, at);
+                true
+            }
+        }
+    }
+
+    /// The time of the next scheduled repaint of any viewport, if any.
+    pub fn next(&self) -> Option<Instant> {
+        self.next_repaint.lock().unwrap().values().min().copied()
+    }
+
+    /// Clears and returns the viewports whose repaint is due by `now`.
+    pub fn take_due(&self, now: Instant) -> Vec<egui::ViewportId> {
+        let mut due = Vec::new();
+        self.next_repaint
+            .lock()
+            .unwrap()
+            .retain(|viewport_id, next| {
+                if *next <= now {
+                    due.push(*viewport_id);
+                    false
+                } else {
+                    true
+                }
+            });
+        due
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn merging_requests_keeps_the_earliest() {
+        let schedule = RepaintSchedule::default();
+        let viewport_id = egui::ViewportId::ROOT;
+
+        assert!(schedule.request_after(viewport_id, Duration::from_secs(10)));
+        let first = schedule.next().unwrap();
+
+        // A later request doesn't postpone the repaint
+        assert!(!schedule.request_after(viewport_id, Duration::from_secs(60)));
+        assert_eq!(schedule.next(), Some(first));
+
+        // An earlier one brings it forward
+        assert!(schedule.request_after(viewport_id, Duration::from_secs(1)));
+        assert!(schedule.next().unwrap() < first);
+
+        // egui not needing another frame leaves it as it was
+        let next = schedule.next();
+        assert!(!schedule.request_after(viewport_id, Duration::MAX));
+        assert_eq!(schedule.next(), next);
+    }
+
+    #[test]
+    fn immediate_repaints_override_delayed_ones() {
+        let schedule = RepaintSchedule::default();
+        let root = egui::ViewportId::ROOT;
+        let child = egui::ViewportId::from_hash_of("child");
+
+        schedule.request_after(root, Duration::from_secs(10));
+        schedule.request_after(child, Duration::from_secs(10));
+        assert!(schedule.take_due(Instant::now()).is_empty());
+
+        assert!(schedule.request_after(root, Duration::ZERO));
+        assert_eq!(schedule.take_due(Instant::now()), vec![root]);
+
+        // Only the due repaint was taken, the delayed one is still scheduled
+        assert!(schedule.next().unwrap() > Instant::now());
+        assert!(schedule.take_due(Instant::now()).is_empty());
+    }
+}
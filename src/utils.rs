@@ -17,12 +17,32 @@ pub(crate) fn get_label_from_tao_id<T: UserEvent>(
     tao_id: &TaoWindowId,
     context: &EventLoopIterationContext<'_, T>,
 ) -> Option<String> {
-    get_id_from_tao_id(tao_id, context).and_then(|id| {
-        context
-            .windows
-            .0
-            .borrow()
-            .get(&id)
-            .map(|ww| ww.label().to_string())
-    })
+    get_id_from_tao_id(tao_id, context).and_then(|id| get_label_from_id(id, context))
+}
+
+/// Gets the label of a Tauri window from its WindowId
+pub(crate) fn get_label_from_id<T: UserEvent>(
+    id: WindowId,
+    context: &EventLoopIterationContext<'_, T>,
+) -> Option<String> {
+    context
+        .windows
+        .0
+        .borrow()
+        .get(&id)
+        .map(|ww| ww.label().to_string())
+}
+
+/// Gets the WindowId of a Tauri window from its label
+pub(crate) fn get_id_from_label<T: UserEvent>(
+    label: &str,
+    context: &EventLoopIterationContext<'_, T>,
+) -> Option<WindowId> {
+    context
+        .windows
+        .0
+        .borrow()
+        .iter()
+        .find(|(_, ww)| ww.label() == label)
+        .map(|(id, _)| *id)
 }
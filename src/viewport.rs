@@ -0,0 +1,435 @@
+//! egui viewports as Tauri windows: carrying out `ViewportCommand`s and
+//! the child viewports apps show with `ctx.show_viewport_deferred`/`show_viewport_immediate`.
+
+use std::sync::Arc;
+
+use egui_wgpu::wgpu;
+use tauri::window::WindowBuilder;
+use tauri::{AppHandle, PhysicalPosition, PhysicalSize, Position, Size, Theme};
+use tauri_runtime::{ResizeDirection, UserAttentionType};
+use tauri_runtime_wry::tao::window::Icon as TaoWindowIcon;
+use tauri_runtime_wry::{UserAttentionTypeWrapper, WindowMessage};
+
+use crate::input::EguiInput;
+use crate::renderer::Renderer;
+
+/// A child viewport rendered into a Tauri window of its own.
+///
+/// It shares the egui context of the window that shows it, so it lives next to
+/// that window's app rather than in the window map.
+pub(crate) struct ViewportWindow {
+    pub label: String,
+    pub window: tauri::Window,
+    /// The viewport showing this one, it's closed along with it
+    pub parent: egui::ViewportId,
+    pub input: EguiInput,
+    renderer: Renderer,
+    /// What egui last asked for, to turn changes into commands
+    builder: egui::ViewportBuilder,
+    /// Draws the viewport, `None` for immediate viewports which are drawn while their parent runs
+    pub ui: Option<Arc<egui::DeferredViewportUiCallback>>,
+    /// Output of the frames since the plugin last handled it
+    pub platform_output: egui::PlatformOutput,
+    /// The error of the last frame if it failed to render
+    pub render_error: Option<wgpu::SurfaceError>,
+    /// Frames in a row that failed to render
+    pub surface_errors: u32,
+    /// `ViewportCommand::Screenshot`s waiting for a rendered frame
+    pending_screenshots: Vec<egui::UserData>,
+}
+
+impl ViewportWindow {
+    pub fn new(
+        window: tauri::Window,
+        renderer: Renderer,
+        input: EguiInput,
+        parent: egui::ViewportId,
+        builder: egui::ViewportBuilder,
+    ) -> Self {
+        Self {
+            label: window.label().to_string(),
+            window,
+            parent,
+            input,
+            renderer,
+            builder,
+            ui: None,
+            platform_output: egui::PlatformOutput::default(),
+            render_error: None,
+            surface_errors: 0,
+            pending_screenshots: Vec::new(),
+        }
+    }
+
+    /// Whether there is nothing to render into.
+    pub fn is_hidden(&self) -> bool {
+        self.input.size.width == 0 || self.input.size.height == 0
+    }
+
+    pub fn resize_surface(&mut self) {
+        let PhysicalSize { width, height } = self.input.size;
+        self.renderer.resize(width, height);
+    }
+
+    /// Runs and renders a frame of the viewport, returning egui's output for all viewports.
+    pub fn run(
+        &mut self,
+        context: &egui::Context,
+        mut ui: impl FnMut(&egui::Context),
+    ) -> egui::ViewportIdMap<egui::ViewportOutput> {
+        let raw_input = self.input.take_egui_input();
+        let egui::FullOutput {
+            textures_delta,
+            shapes,
+            pixels_per_point,
+            platform_output,
+            viewport_output,
+        } = context.run(raw_input, |ctx| ui(ctx));
+
+        self.platform_output.append(platform_output);
+        self.input.zoom_factor = context.zoom_factor();
+
+        if self.is_hidden() {
+            self.renderer.skip_frame(textures_delta);
+            return viewport_output;
+        }
+
+        let paint_jobs = context.tessellate(shapes, pixels_per_point);
+        let screen_descriptor = egui_wgpu::ScreenDescriptor {
+            size_in_pixels: [self.input.size.width, self.input.size.height],
+            pixels_per_point,
+        };
+        let rendered = self.renderer.render_frame(
+            screen_descriptor,
+            &paint_jobs,
+            textures_delta,
+            wgpu::Color::TRANSPARENT,
+        );
+        match rendered {
+            Ok(()) => {
+                self.surface_errors = 0;
+                self.deliver_screenshots(context);
+            }
+            Err(e) => {
+                self.surface_errors += 1;
+                self.render_error = Some(e);
+            }
+        }
+
+        viewport_output
+    }
+
+    /// Reads back the next rendered frame, see [`Self::deliver_screenshots`].
+    pub fn request_screenshot(&mut self, user_data: egui::UserData) {
+        self.pending_screenshots.push(user_data);
+        self.renderer.request_screenshot();
+    }
+
+    /// Hands the screenshot of the rendered frame to the viewport's next frame, like
+    /// the root window does.
+    fn deliver_screenshots(&mut self, context: &egui::Context) {
+        let image = match self.renderer.take_screenshot() {
+            Some(Ok(image)) => Arc::new(image),
+            Some(Err(e)) => {
+                eprintln!("Failed to take a screenshot of {}: {}", self.label, e);
+                self.pending_screenshots.clear();
+                return;
+            }
+            None => return,
+        };
+
+        let viewport_id = self.input.viewport_id;
+        for user_data in self.pending_screenshots.drain(..) {
+            self.input.push_event(egui::Event::Screenshot {
+                viewport_id,
+                user_data,
+                image: image.clone(),
+            });
+        }
+        context.request_repaint_of(viewport_id);
+    }
+
+    /// Takes egui's latest description of the viewport, returning the commands
+    /// that bring the window up to date.
+    pub fn update(&mut self, output: egui::ViewportOutput) -> Vec<egui::ViewportCommand> {
+        self.parent = output.parent;
+        self.ui = output.viewport_ui_cb;
+
+        // Changes that need a new window (e.g. transparency) are left out,
+        // like they are for windows created by Tauri.
+        let (mut commands, _recreate) = self.builder.patch(output.builder);
+        commands.extend(output.commands);
+        commands
+    }
+}
+
+/// A Tauri window as described by an egui viewport builder.
+/// egui sizes are in points, `zoom_factor` converts them to logical pixels.
+pub(crate) fn window_builder<'a>(
+    app: &'a AppHandle,
+    label: &str,
+    builder: &egui::ViewportBuilder,
+    zoom_factor: f32,
+) -> Result<WindowBuilder<'a, tauri::Wry, AppHandle>, tauri::Error> {
+    let logical = |size: egui::Vec2| ((size.x * zoom_factor) as f64, (size.y * zoom_factor) as f64);
+
+    let mut window = WindowBuilder::new(app, label);
+    if let Some(title) = &builder.title {
+        window = window.title(title);
+    }
+    if let Some(position) = builder.position {
+        let (x, y) = logical(position.to_vec2());
+        window = window.position(x, y);
+    }
+    if let Some(size) = builder.inner_size {
+        let (width, height) = logical(size);
+        window = window.inner_size(width, height);
+    }
+    if let Some(size) = builder.min_inner_size.filter(|size| size.is_finite()) {
+        let (width, height) = logical(size);
+        window = window.min_inner_size(width, height);
+    }
+    if let Some(size) = builder.max_inner_size.filter(|size| size.is_finite()) {
+        let (width, height) = logical(size);
+        window = window.max_inner_size(width, height);
+    }
+    if let Some(resizable) = builder.resizable {
+        window = window.resizable(resizable);
+    }
+    if let Some(decorations) = builder.decorations {
+        window = window.decorations(decorations);
+    }
+    if let Some(transparent) = builder.transparent {
+        window = window.transparent(transparent);
+    }
+    if let Some(maximized) = builder.maximized {
+        window = window.maximized(maximized);
+    }
+    if let Some(fullscreen) = builder.fullscreen {
+        window = window.fullscreen(fullscreen);
+    }
+    if let Some(visible) = builder.visible {
+        window = window.visible(visible);
+    }
+    if let Some(active) = builder.active {
+        window = window.focused(active);
+    }
+    if let Some(taskbar) = builder.taskbar {
+        window = window.skip_taskbar(!taskbar);
+    }
+    if let Some(close_button) = builder.close_button {
+        window = window.closable(close_button);
+    }
+    if let Some(minimize_button) = builder.minimize_button {
+        window = window.minimizable(minimize_button);
+    }
+    if let Some(maximize_button) = builder.maximize_button {
+        window = window.maximizable(maximize_button);
+    }
+    match builder.window_level {
+        Some(egui::WindowLevel::AlwaysOnTop) => window = window.always_on_top(true),
+        Some(egui::WindowLevel::AlwaysOnBottom) => window = window.always_on_bottom(true),
+        Some(egui::WindowLevel::Normal) | None => {}
+    }
+    if let Some(icon) = &builder.icon {
+        let icon = tauri::image::Image::new_owned(icon.rgba.clone(), icon.width, icon.height);
+        window = window.icon(icon)?;
+    }
+
+    Ok(window)
+}
+
+/// The window messages doing what `command` asks for, or why it can't be done.
+///
+/// Commands about input (screenshots, IME, clipboard requests) are handled by the
+/// egui window itself and aren't accepted here. egui positions and sizes are in
+/// points, `pixels_per_point` converts them to the window's physical pixels.
+pub(crate) fn window_messages(
+    command: egui::ViewportCommand,
+    pixels_per_point: f32,
+) -> Result<Vec<WindowMessage>, String> {
+    use egui::ViewportCommand;
+
+    let messages = match command {
+        ViewportCommand::Close => vec![WindowMessage::Close],
+        ViewportCommand::Title(title) => vec![WindowMessage::SetTitle(title)],
+        ViewportCommand::Visible(true) => vec![WindowMessage::Show],
+        ViewportCommand::Visible(false) => vec![WindowMessage::Hide],
+        ViewportCommand::StartDrag => vec![WindowMessage::DragWindow],
+        ViewportCommand::OuterPosition(pos) => vec![WindowMessage::SetPosition(physical_position(
+            pos,
+            pixels_per_point,
+        ))],
+        ViewportCommand::InnerSize(size) => match physical_size(size, pixels_per_point) {
+            Some(size) => vec![WindowMessage::SetSize(size)],
+            None => return Err(format!("Can't resize the window to {:?}", size)),
+        },
+        ViewportCommand::MinInnerSize(size) => vec![WindowMessage::SetMinSize(physical_size(
+            size,
+            pixels_per_point,
+        ))],
+        ViewportCommand::MaxInnerSize(size) => vec![WindowMessage::SetMaxSize(physical_size(
+            size,
+            pixels_per_point,
+        ))],
+        ViewportCommand::BeginResize(direction) => {
+            vec![WindowMessage::ResizeDragWindow(resize_direction(direction))]
+        }
+        ViewportCommand::Resizable(resizable) => vec![WindowMessage::SetResizable(resizable)],
+        ViewportCommand::EnableButtons {
+            close,
+            minimized,
+            maximize,
+        } => vec![
+            WindowMessage::SetClosable(close),
+            WindowMessage::SetMinimizable(minimized),
+            WindowMessage::SetMaximizable(maximize),
+        ],
+        ViewportCommand::Minimized(true) => vec![WindowMessage::Minimize],
+        ViewportCommand::Minimized(false) => vec![WindowMessage::Unminimize],
+        ViewportCommand::Maximized(true) => vec![WindowMessage::Maximize],
+        ViewportCommand::Maximized(false) => vec![WindowMessage::Unmaximize],
+        ViewportCommand::Fullscreen(fullscreen) => vec![WindowMessage::SetFullscreen(fullscreen)],
+        ViewportCommand::Decorations(decorations) => {
+            vec![WindowMessage::SetDecorations(decorations)]
+        }
+        // Unset the other level first, Tauri allows both at once
+        ViewportCommand::WindowLevel(level) => match level {
+            egui::WindowLevel::Normal => vec![
+                WindowMessage::SetAlwaysOnTop(false),
+                WindowMessage::SetAlwaysOnBottom(false),
+            ],
+            egui::WindowLevel::AlwaysOnTop => vec![
+                WindowMessage::SetAlwaysOnBottom(false),
+                WindowMessage::SetAlwaysOnTop(true),
+            ],
+            egui::WindowLevel::AlwaysOnBottom => vec![
+                WindowMessage::SetAlwaysOnTop(false),
+                WindowMessage::SetAlwaysOnBottom(true),
+            ],
+        },
+        ViewportCommand::Icon(Some(icon)) => {
+            let icon = TaoWindowIcon::from_rgba(icon.rgba.clone(), icon.width, icon.height)
+                .map_err(|e| format!("Invalid window icon: {}", e))?;
+            vec![WindowMessage::SetIcon(icon)]
+        }
+        ViewportCommand::Icon(None) => {
+            return Err("Tauri can't remove a window's icon".to_string());
+        }
+        ViewportCommand::Focus => vec![WindowMessage::SetFocus],
+        ViewportCommand::RequestUserAttention(attention) => {
+            let attention = match attention {
+                egui::UserAttentionType::Critical => Some(UserAttentionType::Critical),
+                egui::UserAttentionType::Informational => Some(UserAttentionType::Informational),
+                egui::UserAttentionType::Reset => None,
+            };
+            vec![WindowMessage::RequestUserAttention(
+                attention.map(UserAttentionTypeWrapper::from),
+            )]
+        }
+        ViewportCommand::SetTheme(theme) => {
+            let theme = match theme {
+                egui::SystemTheme::SystemDefault => None,
+                egui::SystemTheme::Light => Some(Theme::Light),
+                egui::SystemTheme::Dark => Some(Theme::Dark),
+            };
+            vec![WindowMessage::SetTheme(theme)]
+        }
+        ViewportCommand::ContentProtected(protected) => {
+            vec![WindowMessage::SetContentProtected(protected)]
+        }
+        ViewportCommand::CursorPosition(pos) => vec![WindowMessage::SetCursorPosition(
+            physical_position(pos, pixels_per_point),
+        )],
+        // tao can't lock the cursor in place, confining it is the closest
+        ViewportCommand::CursorGrab(grab) => {
+            vec![WindowMessage::SetCursorGrab(grab != egui::CursorGrab::None)]
+        }
+        ViewportCommand::CursorVisible(visible) => vec![WindowMessage::SetCursorVisible(visible)],
+        ViewportCommand::MousePassthrough(passthrough) => {
+            vec![WindowMessage::SetIgnoreCursorEvents(passthrough)]
+        }
+        ViewportCommand::CancelClose => {
+            return Err("Closing can only be prevented from Tauri's `CloseRequested`".to_string());
+        }
+        ViewportCommand::Transparent(_) => {
+            return Err("Transparency can only be set when creating the window".to_string());
+        }
+        ViewportCommand::ResizeIncrements(_) => {
+            return Err("Tauri windows don't support resize increments".to_string());
+        }
+        command => return Err(format!("{:?} isn't a window command", command)),
+    };
+
+    Ok(messages)
+}
+
+fn physical_position(pos: egui::Pos2, pixels_per_point: f32) -> Position {
+    Position::Physical(PhysicalPosition::new(
+        (pos.x * pixels_per_point).round() as i32,
+        (pos.y * pixels_per_point).round() as i32,
+    ))
+}
+
+/// `None` for sizes egui uses to mean "no limit", e.g. an infinite maximum.
+fn physical_size(size: egui::Vec2, pixels_per_point: f32) -> Option<Size> {
+    if !size.is_finite() || size == egui::Vec2::ZERO {
+        return None;
+    }
+    Some(Size::Physical(PhysicalSize::new(
+        (size.x * pixels_per_point).round() as u32,
+        (size.y * pixels_per_point).round() as u32,
+    )))
+}
+
+fn resize_direction(direction: egui::viewport::ResizeDirection) -> ResizeDirection {
+    match direction {
+        egui::viewport::ResizeDirection::North => ResizeDirection::North,
+        egui::viewport::ResizeDirection::South => ResizeDirection::South,
+        egui::viewport::ResizeDirection::East => ResizeDirection::East,
+        egui::viewport::ResizeDirection::West => ResizeDirection::West,
+        egui::viewport::ResizeDirection::NorthEast => ResizeDirection::NorthEast,
+        egui::viewport::ResizeDirection::SouthEast => ResizeDirection::SouthEast,
+        egui::viewport::ResizeDirection::NorthWest => ResizeDirection::NorthWest,
+        egui::viewport::ResizeDirection::SouthWest => ResizeDirection::SouthWest,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sizes_are_converted_to_physical_pixels() {
+        let messages = window_messages(
+            egui::ViewportCommand::InnerSize(egui::vec2(400.0, 300.0)),
+            2.0,
+        )
+        .unwrap();
+        assert!(matches!(
+            messages.as_slice(),
+            [WindowMessage::SetSize(Size::Physical(PhysicalSize {
+                width: 800,
+                height: 600
+            }))]
+        ));
+    }
+
+    #[test]
+    fn unbounded_max_size_removes_the_limit() {
+        let messages = window_messages(
+            egui::ViewportCommand::MaxInnerSize(egui::Vec2::INFINITY),
+            1.0,
+        )
+        .unwrap();
+        assert!(matches!(
+            messages.as_slice(),
+            [WindowMessage::SetMaxSize(None)]
+        ));
+    }
+
+    #[test]
+    fn input_commands_are_not_window_commands() {
+        assert!(window_messages(egui::ViewportCommand::RequestPaste, 1.0).is_err());
+    }
+}